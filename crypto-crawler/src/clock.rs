@@ -0,0 +1,57 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A source of the current time, used to stamp `Message.received_at`.
+pub trait Clock: Send + Sync {
+    /// Unix timestamp in milliseconds
+    fn now_millis(&self) -> u64;
+}
+
+/// The wall clock, this is the default clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+    }
+}
+
+/// A manually driven clock, useful for deterministic tests.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    millis: AtomicU64,
+}
+
+impl MockClock {
+    pub fn new(millis: u64) -> Self {
+        MockClock { millis: AtomicU64::new(millis) }
+    }
+
+    /// Set the current time in milliseconds.
+    pub fn set(&self, millis: u64) {
+        self.millis.store(millis, Ordering::SeqCst);
+    }
+
+    /// Move the clock forward by `millis` milliseconds.
+    pub fn advance(&self, millis: u64) {
+        self.millis.fetch_add(millis, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_millis(&self) -> u64 {
+        self.millis.load(Ordering::SeqCst)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now_millis(&self) -> u64 {
+        (**self).now_millis()
+    }
+}
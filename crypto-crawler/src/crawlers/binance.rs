@@ -1,9 +1,9 @@
 use core::panic;
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
 use crate::{
-    crawlers::utils::crawl_event, fetch_symbols_retry, get_hot_spot_symbols, msg::Message,
-    utils::cmc_rank::sort_by_cmc_rank,
+    clock::Clock, crawlers::utils::crawl_event, fetch_symbols_retry, get_hot_spot_symbols,
+    msg::Message, utils::cmc_rank::sort_by_cmc_rank,
 };
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
@@ -17,6 +17,8 @@ pub(crate) async fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if market_type == MarketType::EuropeanOption
         && (symbols.is_none() || symbols.unwrap().is_empty())
@@ -26,6 +28,8 @@ pub(crate) async fn crawl_trade(
             MessageType::Trade,
             market_type,
             tx,
+            clock,
+            context,
         );
         let topics: Vec<(String, String)> = vec![
             // ("TICKER_ALL".to_string(), "BTCUSDT".to_string()),
@@ -38,7 +42,8 @@ pub(crate) async fn crawl_trade(
         ws_client.run().await;
         ws_client.close().await;
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::Trade, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::Trade, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
 
@@ -46,6 +51,8 @@ pub(crate) async fn crawl_bbo(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if symbols.is_none() || symbols.unwrap().is_empty() {
         if market_type == MarketType::Spot {
@@ -56,13 +63,16 @@ pub(crate) async fn crawl_bbo(
             });
             sort_by_cmc_rank(EXCHANGE_NAME, &mut hot_spot_symbols);
             let symbols = Some(hot_spot_symbols.as_slice());
-            crawl_event(EXCHANGE_NAME, MessageType::BBO, market_type, symbols, tx).await
+            crawl_event(EXCHANGE_NAME, MessageType::BBO, market_type, symbols, tx, clock, context)
+                .await
         } else {
             let tx = create_conversion_thread(
                 EXCHANGE_NAME.to_string(),
                 MessageType::BBO,
                 market_type,
                 tx,
+                clock,
+                context,
             );
             let commands =
                 vec![r#"{"id":9527,"method":"SUBSCRIBE","params":["!bookTicker"]}"#.to_string()]; // All Book Tickers Stream
@@ -83,7 +93,8 @@ pub(crate) async fn crawl_bbo(
             }
         }
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::BBO, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::BBO, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
 
@@ -91,6 +102,8 @@ pub(crate) async fn crawl_ticker(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if symbols.is_none() || symbols.unwrap().is_empty() {
         let tx = create_conversion_thread(
@@ -98,6 +111,8 @@ pub(crate) async fn crawl_ticker(
             MessageType::Ticker,
             market_type,
             tx,
+            clock,
+            context,
         );
         let commands =
             vec![r#"{"id":9527,"method":"SUBSCRIBE","params":["!ticker@arr"]}"#.to_string()];
@@ -134,7 +149,8 @@ pub(crate) async fn crawl_ticker(
             _ => panic!("Binance {} market does NOT have the ticker channel", market_type),
         }
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::Ticker, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::Ticker, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
 
@@ -143,12 +159,16 @@ pub(crate) async fn crawl_funding_rate(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     let tx = create_conversion_thread(
         EXCHANGE_NAME.to_string(),
         MessageType::FundingRate,
         market_type,
         tx,
+        clock,
+        context,
     );
     let ws_client: Box<dyn WSClient + Send + Sync> = match market_type {
         MarketType::InverseSwap => Box::new(BinanceInverseWSClient::new(tx, None).await),
@@ -2,16 +2,28 @@ use super::{
     crawl_candlestick_ext, crawl_event,
     utils::{check_args, fetch_symbols_retry},
 };
-use crate::{crawlers::utils::create_conversion_thread, msg::Message};
+use crate::{clock::Clock, crawlers::utils::create_conversion_thread, msg::Message};
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
 use crypto_ws_client::*;
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
 const EXCHANGE_NAME: &str = "bitmex";
 
-async fn crawl_all(msg_type: MessageType, tx: Sender<Message>) {
-    let tx = create_conversion_thread(EXCHANGE_NAME.to_string(), msg_type, MarketType::Unknown, tx);
+async fn crawl_all(
+    msg_type: MessageType,
+    tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
+) {
+    let tx = create_conversion_thread(
+        EXCHANGE_NAME.to_string(),
+        msg_type,
+        MarketType::Unknown,
+        tx,
+        clock,
+        context,
+    );
 
     let channel: &str = match msg_type {
         MessageType::Trade => "trade",
@@ -34,12 +46,15 @@ pub(crate) async fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if market_type == MarketType::Unknown {
         // crawl all symbols
-        crawl_all(MessageType::Trade, tx).await;
+        crawl_all(MessageType::Trade, tx, clock, context).await;
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::Trade, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::Trade, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
 
@@ -47,12 +62,15 @@ pub(crate) async fn crawl_l2_event(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if market_type == MarketType::Unknown {
         // crawl all symbols
-        crawl_all(MessageType::L2Event, tx).await;
+        crawl_all(MessageType::L2Event, tx, clock, context).await;
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::L2Event, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::L2Event, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
 
@@ -60,12 +78,15 @@ pub(crate) async fn crawl_bbo(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if market_type == MarketType::Unknown {
         // crawl all symbols
-        crawl_all(MessageType::BBO, tx).await;
+        crawl_all(MessageType::BBO, tx, clock, context).await;
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::BBO, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::BBO, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
 
@@ -73,12 +94,15 @@ pub(crate) async fn crawl_l2_topk(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if market_type == MarketType::Unknown {
         // crawl all symbols
-        crawl_all(MessageType::L2TopK, tx).await;
+        crawl_all(MessageType::L2TopK, tx, clock, context).await;
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::L2TopK, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::L2TopK, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
 
@@ -87,10 +111,12 @@ pub(crate) async fn crawl_funding_rate(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if market_type == MarketType::Unknown {
         // crawl all symbols
-        crawl_all(MessageType::FundingRate, tx).await;
+        crawl_all(MessageType::FundingRate, tx, clock, context).await;
     } else {
         let is_empty = match symbols {
             Some(list) => {
@@ -117,6 +143,8 @@ pub(crate) async fn crawl_funding_rate(
             MessageType::FundingRate,
             market_type,
             tx,
+            clock,
+            context,
         );
 
         let topics: Vec<(String, String)> =
@@ -138,6 +166,8 @@ pub(crate) async fn crawl_candlestick(
     market_type: MarketType,
     symbol_interval_list: Option<&[(String, usize)]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if market_type == MarketType::Unknown {
         let tx = create_conversion_thread(
@@ -145,6 +175,8 @@ pub(crate) async fn crawl_candlestick(
             MessageType::Candlestick,
             market_type,
             tx,
+            clock,
+            context,
         );
 
         let commands = vec![
@@ -157,6 +189,7 @@ pub(crate) async fn crawl_candlestick(
         ws_client.run().await;
         ws_client.close().await;
     } else {
-        crawl_candlestick_ext(EXCHANGE_NAME, market_type, symbol_interval_list, tx).await;
+        crawl_candlestick_ext(EXCHANGE_NAME, market_type, symbol_interval_list, tx, clock, context)
+            .await;
     }
 }
@@ -1,9 +1,9 @@
 use super::crawl_event;
-use crate::{crawlers::utils::create_conversion_thread, msg::Message};
+use crate::{clock::Clock, crawlers::utils::create_conversion_thread, msg::Message};
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
 use crypto_ws_client::*;
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
 const EXCHANGE_NAME: &str = "deribit";
 
@@ -11,6 +11,8 @@ pub(crate) async fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if symbols.is_none() || symbols.unwrap().is_empty() {
         let tx = create_conversion_thread(
@@ -18,6 +20,8 @@ pub(crate) async fn crawl_trade(
             MessageType::Trade,
             market_type,
             tx,
+            clock,
+            context,
         );
 
         // "any" menas all, see https://docs.deribit.com/?javascript#trades-kind-currency-interval
@@ -42,6 +46,7 @@ pub(crate) async fn crawl_trade(
         ws_client.run().await;
         ws_client.close().await;
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::Trade, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::Trade, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
@@ -1,12 +1,13 @@
 use super::utils::fetch_symbols_retry;
 use crate::{
+    clock::Clock,
     crawlers::{crawl_event, utils::create_conversion_thread},
     msg::Message,
 };
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
 use crypto_ws_client::*;
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
 const EXCHANGE_NAME: &str = "huobi";
 
@@ -15,6 +16,8 @@ pub(crate) async fn crawl_l2_event(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     match market_type {
         MarketType::Spot => {
@@ -23,6 +26,8 @@ pub(crate) async fn crawl_l2_event(
                 MessageType::L2Event,
                 market_type,
                 tx,
+                clock,
+                context,
             );
             let symbols: Vec<String> = if symbols.is_none() || symbols.unwrap().is_empty() {
                 tokio::task::block_in_place(move || fetch_symbols_retry(EXCHANGE_NAME, market_type))
@@ -40,7 +45,16 @@ pub(crate) async fn crawl_l2_event(
         | MarketType::LinearSwap
         | MarketType::InverseSwap
         | MarketType::EuropeanOption => {
-            crawl_event(EXCHANGE_NAME, MessageType::L2Event, market_type, symbols, tx).await
+            crawl_event(
+                EXCHANGE_NAME,
+                MessageType::L2Event,
+                market_type,
+                symbols,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
         _ => panic!("Huobi does NOT have the {market_type} market type"),
     }
@@ -51,12 +65,16 @@ pub(crate) async fn crawl_funding_rate(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     let tx = create_conversion_thread(
         EXCHANGE_NAME.to_string(),
         MessageType::FundingRate,
         market_type,
         tx,
+        clock,
+        context,
     );
 
     let symbols: Vec<String> = if symbols.is_none() || symbols.unwrap().is_empty() {
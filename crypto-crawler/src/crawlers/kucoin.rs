@@ -1,8 +1,8 @@
-use crate::{crawlers::utils::create_conversion_thread, msg::Message};
+use crate::{clock::Clock, crawlers::utils::create_conversion_thread, msg::Message};
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
 use crypto_ws_client::*;
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
 use super::crawl_event;
 
@@ -12,10 +12,18 @@ pub(crate) async fn crawl_bbo(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if market_type == MarketType::Spot && (symbols.is_none() || symbols.unwrap().is_empty()) {
-        let tx =
-            create_conversion_thread(EXCHANGE_NAME.to_string(), MessageType::BBO, market_type, tx);
+        let tx = create_conversion_thread(
+            EXCHANGE_NAME.to_string(),
+            MessageType::BBO,
+            market_type,
+            tx,
+            clock,
+            context,
+        );
 
         // https://docs.kucoin.com/#all-symbols-ticker
         let commands: Vec<String> = vec![r#"{"id":"crypto-ws-client","type":"subscribe","topic":"/market/ticker:all","privateChannel":false,"response":true}"#.to_string()];
@@ -24,6 +32,7 @@ pub(crate) async fn crawl_bbo(
         ws_client.run().await;
         ws_client.close().await;
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::BBO, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::BBO, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
@@ -1,9 +1,9 @@
 use super::utils::fetch_symbols_retry;
-use crate::{crawlers::utils::create_conversion_thread, msg::Message};
+use crate::{clock::Clock, crawlers::utils::create_conversion_thread, msg::Message};
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
 use crypto_ws_client::*;
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
 const EXCHANGE_NAME: &str = "okx";
 
@@ -12,12 +12,16 @@ pub(crate) async fn crawl_funding_rate(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     let tx = create_conversion_thread(
         EXCHANGE_NAME.to_string(),
         MessageType::FundingRate,
         market_type,
         tx,
+        clock,
+        context,
     );
 
     let symbols: Vec<String> = if symbols.is_none() || symbols.unwrap().is_empty() {
@@ -45,12 +49,16 @@ pub(crate) async fn crawl_open_interest(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     let tx = create_conversion_thread(
         EXCHANGE_NAME.to_string(),
         MessageType::OpenInterest,
         market_type,
         tx,
+        clock,
+        context,
     );
 
     let symbols = if let Some(symbols) = symbols {
@@ -1,16 +1,20 @@
 use std::{
     sync::{mpsc::Sender, Arc},
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime},
 };
 
 use crate::utils::{REST_LOCKS, WS_LOCKS};
 use crypto_market_type::{get_market_types, MarketType};
 use crypto_markets::fetch_symbols;
-use crypto_rest_client::{fetch_l2_snapshot, fetch_l3_snapshot, fetch_open_interest};
+use crypto_rest_client::{
+    fetch_l2_snapshot, fetch_l3_snapshot, fetch_open_interest, Error as RestError, RateLimitInfo,
+};
 use crypto_ws_client::*;
 use log::*;
 
-use crate::{get_hot_spot_symbols, utils::cmc_rank::sort_by_cmc_rank, Message, MessageType};
+use crate::{
+    clock::Clock, get_hot_spot_symbols, utils::cmc_rank::sort_by_cmc_rank, Message, MessageType,
+};
 
 pub fn fetch_symbols_retry(exchange: &str, market_type: MarketType) -> Vec<String> {
     let retry_count = std::env::var("REST_RETRY_COUNT")
@@ -35,6 +39,20 @@ pub fn fetch_symbols_retry(exchange: &str, market_type: MarketType) -> Vec<Strin
                 break;
             }
             Err(err) => {
+                // crypto-markets reports HTTP errors as strings only
+                let err = err.to_string();
+                if err.contains("451 Unavailable For Legal Reasons") {
+                    if lock_.owns_lock() {
+                        lock_.unlock().unwrap();
+                    }
+                    drop(lock_);
+                    panic!("{exchange} {market_type} is geo-blocked in this region, {err}");
+                }
+                if err.contains("418 I'm a teapot") {
+                    // the ban applies to all processes, keep them waiting on the lock
+                    error!("{exchange} {market_type} IP banned, back off for {IP_BAN_BACKOFF:?}");
+                    std::thread::sleep(IP_BAN_BACKOFF);
+                }
                 backoff_factor *= 2;
                 if i == retry_count - 1 {
                     error!("The {}th time, {}", i, err);
@@ -73,6 +91,10 @@ pub(super) fn check_args(exchange: &str, market_type: MarketType, symbols: &[Str
     }
 }
 
+// How long to back off after an IP ban without `Retry-After`, Binance bans
+// last from 2 minutes to 3 days
+const IP_BAN_BACKOFF: Duration = Duration::from_secs(120);
+
 fn get_cooldown_time_per_request(exchange: &str, market_type: MarketType) -> Duration {
     let millis = match exchange {
         "binance" => 500,    // spot weitht 1200, contract weight 2400
@@ -109,6 +131,8 @@ pub(crate) fn crawl_snapshot(
     msg_type: MessageType, // L2Snapshot or L3Snapshot
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     let is_empty = match symbols {
         Some(list) => {
@@ -153,6 +177,12 @@ pub(crate) fn crawl_snapshot(
                 MessageType::L3Snapshot => fetch_l3_snapshot(exchange, market_type, symbol, None),
                 _ => panic!("msg_type must be L2Snapshot or L3Snapshot"),
             };
+            if let Err(RestError::IpBanned(info)) = &resp {
+                // the ban applies to all processes, keep them waiting on the lock
+                let ban = info.retry_after.unwrap_or(IP_BAN_BACKOFF);
+                error!("{exchange} {market_type} IP banned, back off for {ban:?}");
+                std::thread::sleep(ban);
+            }
             // Cooldown after each request, and make all other processes wait
             // on the lock to avoid parallel requests, thus avoid 429 error
             std::thread::sleep(cooldown_time);
@@ -164,19 +194,33 @@ pub(crate) fn crawl_snapshot(
                     index += 1;
                     success_count += 1;
                     backoff_factor = 1;
-                    let message = Message::new_with_symbol(
+                    let mut message = Message::new_with_clock(
                         exchange.to_string(),
                         market_type,
                         msg_type,
-                        symbol.to_string(),
                         msg,
+                        clock.as_ref(),
                     );
+                    message.symbol = Some(symbol.to_string());
+                    message.context = context.clone();
                     if tx.send(message).is_err() {
                         // break the loop if there is no receiver
                         break 'outer;
                     }
                 }
+                Err(RestError::GeoBlocked(msg)) => {
+                    // retrying can't lift a geo-block
+                    panic!("{exchange} {market_type} is geo-blocked in this region, {msg}");
+                }
                 Err(err) => {
+                    let backoff = match &err {
+                        // wait at least as long as the exchange asks for
+                        RestError::RateLimited(RateLimitInfo {
+                            retry_after: Some(retry_after),
+                            ..
+                        }) => (backoff_factor * cooldown_time).max(*retry_after),
+                        _ => backoff_factor * cooldown_time,
+                    };
                     let current_timestamp = SystemTime::now()
                         .duration_since(SystemTime::UNIX_EPOCH)
                         .unwrap()
@@ -190,9 +234,9 @@ pub(crate) fn crawl_snapshot(
                         market_type,
                         symbol,
                         err,
-                        (backoff_factor * cooldown_time).as_millis()
+                        backoff.as_millis()
                     );
-                    std::thread::sleep(backoff_factor * cooldown_time);
+                    std::thread::sleep(backoff);
                     success_count = 0;
                     backoff_factor += 1;
                 }
@@ -203,7 +247,13 @@ pub(crate) fn crawl_snapshot(
 }
 
 /// Crawl open interests of all trading symbols.
-pub(crate) fn crawl_open_interest(exchange: &str, market_type: MarketType, tx: Sender<Message>) {
+pub(crate) fn crawl_open_interest(
+    exchange: &str,
+    market_type: MarketType,
+    tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
+) {
     let cooldown_time = get_cooldown_time_per_request(exchange, market_type);
 
     let lock = REST_LOCKS.get(exchange).unwrap().get(&market_type).unwrap().clone();
@@ -219,23 +269,27 @@ pub(crate) fn crawl_open_interest(exchange: &str, market_type: MarketType, tx: S
                     if exchange == "deribit" {
                         // A RESTful response of deribit open_interest contains four lines
                         for x in json.trim().split('\n') {
-                            let message = Message::new(
+                            let mut message = Message::new_with_clock(
                                 exchange.to_string(),
                                 market_type,
                                 MessageType::OpenInterest,
                                 x.to_string(),
+                                clock.as_ref(),
                             );
+                            message.context = context.clone();
                             if tx.send(message).is_err() {
                                 break; // break the loop if there is no receiver
                             }
                         }
                     } else {
-                        let message = Message::new(
+                        let mut message = Message::new_with_clock(
                             exchange.to_string(),
                             market_type,
                             MessageType::OpenInterest,
                             json,
+                            clock.as_ref(),
                         );
+                        message.context = context.clone();
                         if tx.send(message).is_err() {
                             break; // break the loop if there is no receiver
                         }
@@ -273,13 +327,15 @@ pub(crate) fn crawl_open_interest(exchange: &str, market_type: MarketType, tx: S
                             index += 1;
                             success_count += 1;
                             backoff_factor = 1;
-                            let message = Message::new_with_symbol(
+                            let mut message = Message::new_with_clock(
                                 exchange.to_string(),
                                 market_type,
                                 MessageType::OpenInterest,
-                                symbol.to_string(),
                                 msg,
+                                clock.as_ref(),
                             );
+                            message.symbol = Some(symbol.to_string());
+                            message.context = context.clone();
                             if tx.send(message).is_err() {
                                 // break the loop if there is no receiver
                                 break 'outer;
@@ -472,8 +528,11 @@ async fn create_ws_client(
     market_type: MarketType,
     msg_type: MessageType,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) -> Arc<dyn WSClient + Send + Sync> {
-    let tx = create_conversion_thread(exchange.to_string(), msg_type, market_type, tx);
+    let tx =
+        create_conversion_thread(exchange.to_string(), msg_type, market_type, tx, clock, context);
     if let Some(interval) = get_connection_interval_ms(exchange, market_type) {
         let lock = WS_LOCKS.get(exchange).unwrap().get(&market_type).unwrap().clone();
         let mut lock = lock.lock().await;
@@ -508,8 +567,9 @@ pub(crate) async fn create_ws_client_symbol(
     exchange: &str,
     market_type: MarketType,
     tx: Sender<String>,
+    clock: Arc<dyn Clock>,
 ) -> Arc<dyn WSClient + Send + Sync> {
-    let tx = create_parser_thread(exchange.to_string(), market_type, tx);
+    let tx = create_parser_thread(exchange.to_string(), market_type, tx, clock);
     create_ws_client_internal(exchange, market_type, tx).await
 }
 
@@ -610,11 +670,20 @@ pub(crate) fn create_conversion_thread(
     msg_type: MessageType,
     market_type: MarketType,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) -> Sender<String> {
     let (tx_raw, rx_raw) = std::sync::mpsc::channel();
     tokio::task::spawn_blocking(move || {
         for json in rx_raw {
-            let msg = Message::new(exchange.clone(), market_type, msg_type, json);
+            let mut msg = Message::new_with_clock(
+                exchange.clone(),
+                market_type,
+                msg_type,
+                json,
+                clock.as_ref(),
+            );
+            msg.context = context.clone();
             if tx.send(msg).is_err() {
                 break; // break the loop if there is no receiver
             }
@@ -628,6 +697,7 @@ fn create_parser_thread(
     exchange: String,
     market_type: MarketType,
     tx: Sender<String>,
+    clock: Arc<dyn Clock>,
 ) -> Sender<String> {
     let (tx_raw, rx_raw) = std::sync::mpsc::channel::<String>();
     std::thread::spawn(move || {
@@ -639,12 +709,7 @@ fn create_parser_thread(
                 )
                 .unwrap(),
                 MessageType::L2Event => {
-                    let received_at = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_millis()
-                        .try_into()
-                        .unwrap();
+                    let received_at = clock.now_millis() as i64;
                     serde_json::to_string(
                         &crypto_msg_parser::parse_l2(
                             &exchange,
@@ -666,21 +731,13 @@ fn create_parser_thread(
     tx_raw
 }
 
-async fn crawl_event_one_chunk(
+fn crawl_event_one_chunk(
     exchange: String,
     market_type: MarketType,
     msg_type: MessageType,
-    ws_client: Option<Arc<dyn WSClient + Send + Sync>>,
+    ws_client: Arc<dyn WSClient + Send + Sync>,
     symbols: Vec<String>,
-    tx: Sender<Message>,
 ) -> tokio::task::JoinHandle<()> {
-    let ws_client = if let Some(ws_client) = ws_client {
-        ws_client
-    } else {
-        let tx_clone = tx.clone();
-        create_ws_client(&exchange, market_type, msg_type, tx_clone).await
-    };
-
     {
         // fire and forget
         let exchange_clone = exchange.to_string();
@@ -703,6 +760,8 @@ pub(crate) async fn crawl_event(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     let num_topics_per_connection = get_num_subscriptions_per_connection(exchange, market_type);
     let is_empty = match symbols {
@@ -748,7 +807,7 @@ pub(crate) async fn crawl_event(
 
     // create a thread to convert Sender<String> to Sender<Message>
     if real_symbols.len() <= num_topics_per_connection {
-        let ws_client = create_ws_client(exchange, market_type, msg_type, tx).await;
+        let ws_client = create_ws_client(exchange, market_type, msg_type, tx, clock, context).await;
         subscribe_with_lock(
             exchange.to_string(),
             market_type,
@@ -785,21 +844,26 @@ pub(crate) async fn crawl_event(
         {
             let n = chunks.len();
             for (i, chunk) in chunks.into_iter().enumerate() {
-                last_ws_client = if i == (n - 1) {
-                    let tx_clone = tx.clone();
-                    Some(create_ws_client(exchange, market_type, msg_type, tx_clone).await)
-                } else {
-                    None
-                };
+                let ws_client = create_ws_client(
+                    exchange,
+                    market_type,
+                    msg_type,
+                    tx.clone(),
+                    clock.clone(),
+                    context.clone(),
+                )
+                .await;
+                if i == (n - 1) {
+                    last_ws_client = Some(ws_client.clone());
+                }
                 let ret = crawl_event_one_chunk(
                     exchange.to_string(),
                     market_type,
                     msg_type,
-                    last_ws_client.clone(),
+                    ws_client,
                     chunk,
-                    tx.clone(),
                 );
-                handles.push(ret.await);
+                handles.push(ret);
             }
             drop(tx);
         }
@@ -855,12 +919,15 @@ async fn crawl_candlestick_one_chunk(
     ws_client: Option<Arc<dyn WSClient + Send + Sync>>,
     symbol_interval_list: Vec<(String, usize)>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) -> tokio::task::JoinHandle<()> {
     let ws_client = if let Some(ws_client) = ws_client {
         ws_client
     } else {
         let tx_clone = tx.clone();
-        create_ws_client(&exchange, market_type, MessageType::Candlestick, tx_clone).await
+        create_ws_client(&exchange, market_type, MessageType::Candlestick, tx_clone, clock, context)
+            .await
     };
 
     {
@@ -882,6 +949,8 @@ pub(crate) async fn crawl_candlestick_ext(
     market_type: MarketType,
     symbol_interval_list: Option<&[(String, usize)]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     let num_topics_per_connection = get_num_subscriptions_per_connection(exchange, market_type);
     let is_empty = match symbol_interval_list {
@@ -937,7 +1006,9 @@ pub(crate) async fn crawl_candlestick_ext(
     };
 
     if symbol_interval_list.len() <= num_topics_per_connection {
-        let ws_client = create_ws_client(exchange, market_type, MessageType::Candlestick, tx).await;
+        let ws_client =
+            create_ws_client(exchange, market_type, MessageType::Candlestick, tx, clock, context)
+                .await;
         ws_client.subscribe_candlestick(&symbol_interval_list).await;
         if automatic_symbol_discovery {
             create_new_symbol_receiver_thread_candlestick(
@@ -970,8 +1041,15 @@ pub(crate) async fn crawl_candlestick_ext(
                 last_ws_client = if i == (n - 1) {
                     let tx_clone = tx.clone();
                     Some(
-                        create_ws_client(exchange, market_type, MessageType::Candlestick, tx_clone)
-                            .await,
+                        create_ws_client(
+                            exchange,
+                            market_type,
+                            MessageType::Candlestick,
+                            tx_clone,
+                            clock.clone(),
+                            context.clone(),
+                        )
+                        .await,
                     )
                 } else {
                     None
@@ -982,6 +1060,8 @@ pub(crate) async fn crawl_candlestick_ext(
                     last_ws_client.clone(),
                     chunk,
                     tx.clone(),
+                    clock.clone(),
+                    context.clone(),
                 );
                 handles.push(ret.await);
             }
@@ -1005,3 +1085,48 @@ pub(crate) async fn crawl_candlestick_ext(
         _ = thread.await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_conversion_thread_uses_clock() {
+        let clock = Arc::new(MockClock::new(1_616_271_105_000));
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx_raw = create_conversion_thread(
+            "binance".to_string(),
+            MessageType::Trade,
+            MarketType::Spot,
+            tx,
+            clock.clone(),
+            None,
+        );
+
+        tx_raw.send(r#"{"stream":"btcusdt@aggTrade","data":{}}"#.to_string()).unwrap();
+        assert_eq!(1_616_271_105_000, rx.recv().unwrap().received_at);
+
+        clock.advance(5);
+        tx_raw.send(r#"{"stream":"btcusdt@aggTrade","data":{}}"#.to_string()).unwrap();
+        assert_eq!(1_616_271_105_005, rx.recv().unwrap().received_at);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_conversion_thread_sets_context() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx_raw = create_conversion_thread(
+            "binance".to_string(),
+            MessageType::Trade,
+            MarketType::Spot,
+            tx,
+            Arc::new(MockClock::new(1_616_271_105_000)),
+            Some("shard-1".to_string()),
+        );
+
+        tx_raw.send(r#"{"stream":"btcusdt@aggTrade","data":{}}"#.to_string()).unwrap();
+        let msg = rx.recv().unwrap();
+        assert_eq!(Some("shard-1".to_string()), msg.context);
+        assert!(msg.to_string().contains(r#""context":"shard-1""#));
+    }
+}
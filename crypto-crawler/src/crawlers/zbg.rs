@@ -1,6 +1,6 @@
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
-use crate::{crawlers::utils::crawl_event, msg::Message};
+use crate::{clock::Clock, crawlers::utils::crawl_event, msg::Message};
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
 use crypto_ws_client::*;
@@ -13,6 +13,8 @@ pub(crate) async fn crawl_ticker(
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
+    clock: Arc<dyn Clock>,
+    context: Option<String>,
 ) {
     if symbols.is_none() || symbols.unwrap().is_empty() {
         if market_type == MarketType::Spot {
@@ -21,6 +23,8 @@ pub(crate) async fn crawl_ticker(
                 MessageType::Ticker,
                 market_type,
                 tx,
+                clock,
+                context,
             );
             let commands: Vec<String> =
                 vec![r#"{"action":"ADD", "dataType":"ALL_TRADE_STATISTIC_24H"}"#.to_string()];
@@ -35,6 +39,8 @@ pub(crate) async fn crawl_ticker(
                 MessageType::Ticker,
                 market_type,
                 tx,
+                clock,
+                context,
             );
             let commands: Vec<String> =
                 vec![r#"{"action":"sub", "topic":"future_all_indicator"}"#.to_string()];
@@ -45,6 +51,7 @@ pub(crate) async fn crawl_ticker(
             ws_client.close().await;
         }
     } else {
-        crawl_event(EXCHANGE_NAME, MessageType::Ticker, market_type, symbols, tx).await;
+        crawl_event(EXCHANGE_NAME, MessageType::Ticker, market_type, symbols, tx, clock, context)
+            .await;
     }
 }
@@ -213,16 +213,20 @@
 //!     assert!(!messages.is_empty());
 //! }
 //! ```
+mod clock;
 mod crawlers;
 mod msg;
+mod options;
 mod utils;
 
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
+pub use clock::{Clock, MockClock, SystemClock};
 pub use crawlers::fetch_symbols_retry;
 pub use crypto_market_type::MarketType;
 pub use crypto_msg_type::MessageType;
 pub use msg::*;
+pub use options::CrawlOptions;
 pub use utils::get_hot_spot_symbols;
 
 /// Crawl realtime trades.
@@ -236,14 +240,35 @@ pub async fn crawl_trade(
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
+    crawl_trade_with_options(exchange, market_type, symbols, tx, CrawlOptions::default()).await;
+}
+
+/// Same as `crawl_trade()`, but with `options`.
+pub async fn crawl_trade_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
     match exchange {
-        "binance" => crawlers::binance::crawl_trade(market_type, symbols, tx).await,
-        "bitmex" => crawlers::bitmex::crawl_trade(market_type, symbols, tx).await,
-        "deribit" => crawlers::deribit::crawl_trade(market_type, symbols, tx).await,
+        "binance" => crawlers::binance::crawl_trade(market_type, symbols, tx, clock, context).await,
+        "bitmex" => crawlers::bitmex::crawl_trade(market_type, symbols, tx, clock, context).await,
+        "deribit" => crawlers::deribit::crawl_trade(market_type, symbols, tx, clock, context).await,
         "bitfinex" | "bitget" | "bithumb" | "bitstamp" | "bitz" | "bybit" | "coinbase_pro"
         | "dydx" | "ftx" | "gate" | "huobi" | "kraken" | "kucoin" | "mexc" | "okx" | "zb"
         | "zbg" => {
-            crawlers::crawl_event(exchange, MessageType::Trade, market_type, symbols, tx).await
+            crawlers::crawl_event(
+                exchange,
+                MessageType::Trade,
+                market_type,
+                symbols,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
         _ => panic!("{exchange} does NOT have the trade websocket channel"),
     }
@@ -256,13 +281,36 @@ pub async fn crawl_l2_event(
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
+    crawl_l2_event_with_options(exchange, market_type, symbols, tx, CrawlOptions::default()).await;
+}
+
+/// Same as `crawl_l2_event()`, but with `options`.
+pub async fn crawl_l2_event_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
     match exchange {
-        "bitmex" => crawlers::bitmex::crawl_l2_event(market_type, symbols, tx).await,
-        "huobi" => crawlers::huobi::crawl_l2_event(market_type, symbols, tx).await,
+        "bitmex" => {
+            crawlers::bitmex::crawl_l2_event(market_type, symbols, tx, clock, context).await
+        }
+        "huobi" => crawlers::huobi::crawl_l2_event(market_type, symbols, tx, clock, context).await,
         "binance" | "bitfinex" | "bitget" | "bithumb" | "bitstamp" | "bitz" | "bybit"
         | "coinbase_pro" | "deribit" | "dydx" | "ftx" | "gate" | "kraken" | "kucoin" | "mexc"
         | "okx" | "zb" | "zbg" => {
-            crawlers::crawl_event(exchange, MessageType::L2Event, market_type, symbols, tx).await
+            crawlers::crawl_event(
+                exchange,
+                MessageType::L2Event,
+                market_type,
+                symbols,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
         _ => panic!("{exchange} does NOT have the incremental level2 websocket channel"),
     }
@@ -275,22 +323,69 @@ pub async fn crawl_l3_event(
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
+    crawl_l3_event_with_options(exchange, market_type, symbols, tx, CrawlOptions::default()).await;
+}
+
+/// Same as `crawl_l3_event()`, but with `options`.
+pub async fn crawl_l3_event_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
     match exchange {
         "bitfinex" | "bitstamp" | "coinbase_pro" | "kucoin" => {
-            crawlers::crawl_event(exchange, MessageType::L3Event, market_type, symbols, tx).await
+            crawlers::crawl_event(
+                exchange,
+                MessageType::L3Event,
+                market_type,
+                symbols,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
         _ => panic!("{exchange} does NOT have the incremental level3 websocket channel"),
     }
 }
 
 /// Crawl level2 orderbook snapshots through RESTful APIs.
+///
+/// When the exchange rate limits requests, e.g., Binance HTTP 429, the crawler
+/// backs off for at least the `Retry-After` duration before the next request.
+/// After an IP ban, e.g., Binance HTTP 418, all requests pause until the ban
+/// is lifted. This function panics if the exchange is geo-blocked, e.g.,
+/// Binance HTTP 451, since retrying won't help.
 pub fn crawl_l2_snapshot(
     exchange: &str,
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
-    crawlers::crawl_snapshot(exchange, market_type, MessageType::L2Snapshot, symbols, tx);
+    crawl_l2_snapshot_with_options(exchange, market_type, symbols, tx, CrawlOptions::default());
+}
+
+/// Same as `crawl_l2_snapshot()`, but with `options`.
+pub fn crawl_l2_snapshot_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
+    crawlers::crawl_snapshot(
+        exchange,
+        market_type,
+        MessageType::L2Snapshot,
+        symbols,
+        tx,
+        clock,
+        context,
+    );
 }
 
 /// Crawl best bid and ask.
@@ -300,12 +395,33 @@ pub async fn crawl_bbo(
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
+    crawl_bbo_with_options(exchange, market_type, symbols, tx, CrawlOptions::default()).await;
+}
+
+/// Same as `crawl_bbo()`, but with `options`.
+pub async fn crawl_bbo_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
     match exchange {
-        "binance" => crawlers::binance::crawl_bbo(market_type, symbols, tx).await,
-        "bitmex" => crawlers::bitmex::crawl_bbo(market_type, symbols, tx).await,
-        "kucoin" => crawlers::kucoin::crawl_bbo(market_type, symbols, tx).await,
+        "binance" => crawlers::binance::crawl_bbo(market_type, symbols, tx, clock, context).await,
+        "bitmex" => crawlers::bitmex::crawl_bbo(market_type, symbols, tx, clock, context).await,
+        "kucoin" => crawlers::kucoin::crawl_bbo(market_type, symbols, tx, clock, context).await,
         "deribit" | "ftx" | "gate" | "huobi" | "kraken" | "okx" => {
-            crawlers::crawl_event(exchange, MessageType::BBO, market_type, symbols, tx).await
+            crawlers::crawl_event(
+                exchange,
+                MessageType::BBO,
+                market_type,
+                symbols,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
         _ => panic!("{exchange} does NOT have BBO websocket channel"),
     }
@@ -318,24 +434,67 @@ pub async fn crawl_l2_topk(
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
+    crawl_l2_topk_with_options(exchange, market_type, symbols, tx, CrawlOptions::default()).await;
+}
+
+/// Same as `crawl_l2_topk()`, but with `options`.
+pub async fn crawl_l2_topk_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
     match exchange {
-        "bitmex" => crawlers::bitmex::crawl_l2_topk(market_type, symbols, tx).await,
+        "bitmex" => crawlers::bitmex::crawl_l2_topk(market_type, symbols, tx, clock, context).await,
         "binance" | "bitget" | "bybit" | "bitstamp" | "deribit" | "gate" | "huobi" | "kucoin"
         | "mexc" | "okx" | "zb" => {
-            crawlers::crawl_event(exchange, MessageType::L2TopK, market_type, symbols, tx).await
+            crawlers::crawl_event(
+                exchange,
+                MessageType::L2TopK,
+                market_type,
+                symbols,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
         _ => panic!("{exchange} does NOT have the level2 top-k snapshot websocket channel"),
     }
 }
 
 /// Crawl level3 orderbook snapshots through RESTful APIs.
+///
+/// Rate limits, IP bans and geo-blocks are handled like `crawl_l2_snapshot()`.
 pub fn crawl_l3_snapshot(
     exchange: &str,
     market_type: MarketType,
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
-    crawlers::crawl_snapshot(exchange, market_type, MessageType::L3Snapshot, symbols, tx)
+    crawl_l3_snapshot_with_options(exchange, market_type, symbols, tx, CrawlOptions::default());
+}
+
+/// Same as `crawl_l3_snapshot()`, but with `options`.
+pub fn crawl_l3_snapshot_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
+    crawlers::crawl_snapshot(
+        exchange,
+        market_type,
+        MessageType::L3Snapshot,
+        symbols,
+        tx,
+        clock,
+        context,
+    )
 }
 
 /// Crawl 24hr rolling window ticker.
@@ -348,14 +507,37 @@ pub async fn crawl_ticker(
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
+    crawl_ticker_with_options(exchange, market_type, symbols, tx, CrawlOptions::default()).await;
+}
+
+/// Same as `crawl_ticker()`, but with `options`.
+pub async fn crawl_ticker_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
     match exchange {
-        "binance" => crawlers::binance::crawl_ticker(market_type, symbols, tx).await,
+        "binance" => {
+            crawlers::binance::crawl_ticker(market_type, symbols, tx, clock, context).await
+        }
         "bitfinex" | "bitget" | "bithumb" | "bitz" | "bybit" | "coinbase_pro" | "deribit"
         | "gate" | "huobi" | "kraken" | "kucoin" | "mexc" | "okx" => {
-            crawlers::crawl_event(exchange, MessageType::Ticker, market_type, symbols, tx).await
+            crawlers::crawl_event(
+                exchange,
+                MessageType::Ticker,
+                market_type,
+                symbols,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
-        "zb" => crawlers::zb::crawl_ticker(market_type, symbols, tx).await,
-        "zbg" => crawlers::zbg::crawl_ticker(market_type, symbols, tx).await,
+        "zb" => crawlers::zb::crawl_ticker(market_type, symbols, tx, clock, context).await,
+        "zbg" => crawlers::zbg::crawl_ticker(market_type, symbols, tx, clock, context).await,
         _ => panic!("{exchange} does NOT have the ticker websocket channel"),
     }
 }
@@ -367,11 +549,30 @@ pub async fn crawl_funding_rate(
     symbols: Option<&[String]>,
     tx: Sender<Message>,
 ) {
+    crawl_funding_rate_with_options(exchange, market_type, symbols, tx, CrawlOptions::default())
+        .await;
+}
+
+/// Same as `crawl_funding_rate()`, but with `options`.
+pub async fn crawl_funding_rate_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
     match exchange {
-        "binance" => crawlers::binance::crawl_funding_rate(market_type, symbols, tx).await,
-        "bitmex" => crawlers::bitmex::crawl_funding_rate(market_type, symbols, tx).await,
-        "huobi" => crawlers::huobi::crawl_funding_rate(market_type, symbols, tx).await,
-        "okx" => crawlers::okx::crawl_funding_rate(market_type, symbols, tx).await,
+        "binance" => {
+            crawlers::binance::crawl_funding_rate(market_type, symbols, tx, clock, context).await
+        }
+        "bitmex" => {
+            crawlers::bitmex::crawl_funding_rate(market_type, symbols, tx, clock, context).await
+        }
+        "huobi" => {
+            crawlers::huobi::crawl_funding_rate(market_type, symbols, tx, clock, context).await
+        }
+        "okx" => crawlers::okx::crawl_funding_rate(market_type, symbols, tx, clock, context).await,
         _ => panic!("{exchange} does NOT have perpetual swap market"),
     }
 }
@@ -386,13 +587,47 @@ pub async fn crawl_candlestick(
     symbol_interval_list: Option<&[(String, usize)]>,
     tx: Sender<Message>,
 ) {
+    crawl_candlestick_with_options(
+        exchange,
+        market_type,
+        symbol_interval_list,
+        tx,
+        CrawlOptions::default(),
+    )
+    .await;
+}
+
+/// Same as `crawl_candlestick()`, but with `options`.
+pub async fn crawl_candlestick_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    symbol_interval_list: Option<&[(String, usize)]>,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    let CrawlOptions { clock, context } = options;
     match exchange {
         "bitmex" => {
-            crawlers::bitmex::crawl_candlestick(market_type, symbol_interval_list, tx).await
+            crawlers::bitmex::crawl_candlestick(
+                market_type,
+                symbol_interval_list,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
         "binance" | "bitfinex" | "bitget" | "bitz" | "bybit" | "deribit" | "gate" | "huobi"
         | "kraken" | "kucoin" | "mexc" | "okx" | "zb" | "zbg" => {
-            crawlers::crawl_candlestick_ext(exchange, market_type, symbol_interval_list, tx).await
+            crawlers::crawl_candlestick_ext(
+                exchange,
+                market_type,
+                symbol_interval_list,
+                tx,
+                clock,
+                context,
+            )
+            .await
         }
         _ => panic!("{exchange} does NOT have the candlestick websocket channel"),
     };
@@ -400,7 +635,17 @@ pub async fn crawl_candlestick(
 
 /// Crawl all open interest.
 pub fn crawl_open_interest(exchange: &str, market_type: MarketType, tx: Sender<Message>) {
-    crawlers::crawl_open_interest(exchange, market_type, tx);
+    crawl_open_interest_with_options(exchange, market_type, tx, CrawlOptions::default());
+}
+
+/// Same as `crawl_open_interest()`, but with `options`.
+pub fn crawl_open_interest_with_options(
+    exchange: &str,
+    market_type: MarketType,
+    tx: Sender<Message>,
+    options: CrawlOptions,
+) {
+    crawlers::crawl_open_interest(exchange, market_type, tx, options.clock, options.context);
 }
 
 /// Subscribe to multiple message types of one symbol.
@@ -415,7 +660,8 @@ pub async fn subscribe_symbol(
     msg_types: &[MessageType],
     tx: Sender<String>,
 ) {
-    let ws_client = crawlers::create_ws_client_symbol(exchange, market_type, tx).await;
+    let ws_client =
+        crawlers::create_ws_client_symbol(exchange, market_type, tx, Arc::new(SystemClock)).await;
     let symbols = vec![symbol.to_string()];
     let commands = crypto_msg_type::get_ws_commands(exchange, msg_types, &symbols, true, None);
     ws_client.send(&commands).await;
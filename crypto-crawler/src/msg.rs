@@ -1,11 +1,9 @@
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
 use serde::{Deserialize, Serialize};
-use std::{
-    convert::TryInto,
-    str::FromStr,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::str::FromStr;
+
+use crate::clock::{Clock, SystemClock};
 
 /// Message represents messages received by crawlers.
 #[derive(Serialize, Deserialize)]
@@ -22,6 +20,9 @@ pub struct Message {
     pub received_at: u64,
     /// the original message
     pub json: String,
+    /// Caller-provided context, e.g., a shard id, see `CrawlOptions`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
 }
 
 impl Message {
@@ -30,19 +31,26 @@ impl Message {
         market_type: MarketType,
         msg_type: MessageType,
         json: String,
+    ) -> Self {
+        Self::new_with_clock(exchange, market_type, msg_type, json, &SystemClock)
+    }
+
+    /// Same as `new()`, but `received_at` is read from `clock`.
+    pub fn new_with_clock(
+        exchange: String,
+        market_type: MarketType,
+        msg_type: MessageType,
+        json: String,
+        clock: &dyn Clock,
     ) -> Self {
         Message {
             exchange,
             market_type,
             msg_type,
             symbol: None,
-            received_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_millis()
-                .try_into()
-                .unwrap(),
+            received_at: clock.now_millis(),
             json: json.trim().to_string(),
+            context: None,
         }
     }
 
@@ -83,6 +91,7 @@ impl Message {
             symbol,
             received_at: v[0].parse::<u64>().unwrap(),
             json: v[2].to_string(),
+            context: None,
         }
     }
 }
@@ -92,3 +101,45 @@ impl std::fmt::Display for Message {
         write!(f, "{}", serde_json::to_string(self).unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn test_new_with_clock() {
+        let clock = MockClock::new(1_616_271_105_000);
+        let msg = Message::new_with_clock(
+            "binance".to_string(),
+            MarketType::Spot,
+            MessageType::Trade,
+            r#"{"stream":"btcusdt@aggTrade","data":{}}"#.to_string(),
+            &clock,
+        );
+        assert_eq!(1_616_271_105_000, msg.received_at);
+
+        clock.advance(5);
+        let msg = Message::new_with_clock(
+            "binance".to_string(),
+            MarketType::Spot,
+            MessageType::Trade,
+            r#"{"stream":"btcusdt@aggTrade","data":{}}"#.to_string(),
+            &clock,
+        );
+        assert_eq!(1_616_271_105_005, msg.received_at);
+    }
+
+    #[test]
+    fn test_no_context() {
+        let msg = Message::new(
+            "binance".to_string(),
+            MarketType::Spot,
+            MessageType::Trade,
+            r#"{"stream":"btcusdt@aggTrade","data":{}}"#.to_string(),
+        );
+        assert!(!msg.to_string().contains("context"));
+        let msg: Message = serde_json::from_str(&msg.to_string()).unwrap();
+        assert_eq!(None, msg.context);
+    }
+}
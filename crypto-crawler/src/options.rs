@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use crate::clock::{Clock, SystemClock};
+
+/// Options of the `crawl_*_with_options()` functions.
+///
+/// ```rust
+/// use crypto_crawler::{CrawlOptions, MockClock};
+/// use std::sync::Arc;
+///
+/// let options = CrawlOptions {
+///     clock: Arc::new(MockClock::new(0)),
+///     context: Some("shard-1".to_string()),
+/// };
+/// ```
+#[derive(Clone)]
+pub struct CrawlOptions {
+    /// Stamps `Message.received_at`, the system clock by default
+    pub clock: Arc<dyn Clock>,
+    /// Copied into `Message.context` of every message, e.g., a shard id,
+    /// useful when multiple crawlers share one `tx`
+    pub context: Option<String>,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        CrawlOptions { clock: Arc::new(SystemClock), context: None }
+    }
+}
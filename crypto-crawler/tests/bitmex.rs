@@ -6,6 +6,7 @@ use test_case::test_case;
 use crypto_crawler::*;
 use crypto_market_type::MarketType;
 use crypto_msg_type::MessageType;
+use std::sync::Arc;
 use utils::parse;
 
 const EXCHANGE_NAME: &str = "bitmex";
@@ -75,6 +76,25 @@ async fn test_crawl_funding_rate_all() {
     crawl_all(MessageType::FundingRate).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crawl_trade_with_options() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    tokio::task::spawn(async move {
+        let options = CrawlOptions {
+            clock: Arc::new(MockClock::new(1_616_271_105_000)),
+            context: Some("shard-1".to_string()),
+        };
+        crawl_trade_with_options(EXCHANGE_NAME, MarketType::Unknown, None, tx, options).await;
+    });
+
+    let msg = rx.recv().unwrap();
+
+    assert_eq!(msg.exchange, EXCHANGE_NAME.to_string());
+    assert_eq!(msg.msg_type, MessageType::Trade);
+    assert_eq!(msg.received_at, 1_616_271_105_000);
+    assert_eq!(msg.context, Some("shard-1".to_string()));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_crawl_candlestick_rate_all() {
     let (tx, rx) = std::sync::mpsc::channel();
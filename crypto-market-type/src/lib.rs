@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use strum_macros::{Display, EnumString};
+use strum_macros::{AsRefStr, Display, EnumString, IntoStaticStr};
 
 /// Market type.
 ///
@@ -49,10 +49,79 @@ pub enum MarketType {
     BVOL,
 }
 
+/// Cryptocurrency exchanges.
+///
+/// Converts from and to the exchange names used across this workspace, e.g.,
+/// `"binance"` and `"coinbase_pro"`, the legacy names `"okex"` and `"mxc"`
+/// are accepted too. `&'static str` names are available via `AsRef<str>` and
+/// `Into<&'static str>` without allocating.
+///
+/// ```
+/// use crypto_market_type::Exchange;
+///
+/// assert_eq!(Exchange::CoinbasePro, "coinbase_pro".parse().unwrap());
+/// assert_eq!(Exchange::Okx, "okex".parse().unwrap());
+/// assert_eq!("okx", Exchange::Okx.to_string());
+/// assert_eq!("coinbase", Exchange::Coinbase.as_ref());
+/// let name: &'static str = Exchange::Mexc.into();
+/// assert_eq!("mexc", name);
+/// assert!("binanace".parse::<Exchange>().is_err());
+/// ```
+#[derive(
+    Copy,
+    Clone,
+    Serialize,
+    Deserialize,
+    Display,
+    Debug,
+    EnumString,
+    AsRefStr,
+    IntoStaticStr,
+    PartialEq,
+    Hash,
+    Eq,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Exchange {
+    Binance,
+    Bitfinex,
+    Bitget,
+    Bithumb,
+    Bitmex,
+    Bitstamp,
+    Bitz,
+    Bybit,
+    Coinbase,
+    CoinbasePro,
+    Deribit,
+    Dydx,
+    Ftx,
+    Gate,
+    Huobi,
+    Kraken,
+    Kucoin,
+    #[serde(alias = "mxc")]
+    #[strum(to_string = "mexc", serialize = "mxc")]
+    Mexc,
+    #[serde(alias = "okex")]
+    #[strum(to_string = "okx", serialize = "okex")]
+    Okx,
+    Zb,
+    Zbg,
+}
+
 /// Get market types of a cryptocurrency exchange.
 pub fn get_market_types(exchange: &str) -> Vec<MarketType> {
+    match exchange.parse::<Exchange>() {
+        Ok(exchange) => exchange_market_types(exchange),
+        Err(_) => panic!("Unknown exchange {exchange}"),
+    }
+}
+
+fn exchange_market_types(exchange: Exchange) -> Vec<MarketType> {
     match exchange {
-        "binance" => vec![
+        Exchange::Binance => vec![
             MarketType::Spot,
             MarketType::LinearFuture,
             MarketType::InverseFuture,
@@ -60,17 +129,17 @@ pub fn get_market_types(exchange: &str) -> Vec<MarketType> {
             MarketType::InverseSwap,
             // MarketType::EuropeanOption, // binance has shutdown option markets.
         ],
-        "bitfinex" => vec![MarketType::Spot, MarketType::LinearSwap],
-        "bitget" => vec![
+        Exchange::Bitfinex => vec![MarketType::Spot, MarketType::LinearSwap],
+        Exchange::Bitget => vec![
             MarketType::Spot,
             MarketType::InverseSwap, /* TODO: Bitget's coin-margined swap market is a kind of
                                       * mixed contract */
             MarketType::LinearSwap,
             MarketType::InverseFuture,
         ],
-        "bithumb" => vec![MarketType::Spot],
+        Exchange::Bithumb => vec![MarketType::Spot],
         // BitMEX only handles Bitcoin. All profit and loss is in Bitcoin
-        "bitmex" => vec![
+        Exchange::Bitmex => vec![
             MarketType::Spot,
             MarketType::LinearSwap,
             MarketType::InverseSwap,
@@ -79,47 +148,51 @@ pub fn get_market_types(exchange: &str) -> Vec<MarketType> {
             MarketType::InverseFuture,
             MarketType::QuantoFuture,
         ],
-        "bitstamp" => vec![MarketType::Spot],
-        "bitz" => vec![MarketType::Spot, MarketType::InverseSwap, MarketType::LinearSwap],
-        "bybit" => vec![MarketType::InverseSwap, MarketType::LinearSwap, MarketType::InverseFuture],
-        "coinbase_pro" => vec![MarketType::Spot],
+        Exchange::Bitstamp => vec![MarketType::Spot],
+        Exchange::Bitz => vec![MarketType::Spot, MarketType::InverseSwap, MarketType::LinearSwap],
+        Exchange::Bybit => {
+            vec![MarketType::InverseSwap, MarketType::LinearSwap, MarketType::InverseFuture]
+        }
+        Exchange::Coinbase | Exchange::CoinbasePro => vec![MarketType::Spot],
         // Deribit only accepts Bitcoin as funds to deposit.
-        "deribit" => vec![
+        Exchange::Deribit => vec![
             MarketType::InverseFuture,
             MarketType::InverseSwap,
             MarketType::EuropeanOption, // inverse
         ],
-        "dydx" => vec![MarketType::LinearSwap],
-        "ftx" => vec![
+        Exchange::Dydx => vec![MarketType::LinearSwap],
+        Exchange::Ftx => vec![
             MarketType::Spot,
             MarketType::LinearFuture,
             MarketType::LinearSwap,
             MarketType::Move,
             MarketType::BVOL,
         ],
-        "gate" => vec![
+        Exchange::Gate => vec![
             MarketType::Spot,
             MarketType::InverseFuture,
             MarketType::LinearFuture,
             MarketType::InverseSwap,
             MarketType::LinearSwap,
         ],
-        "huobi" => vec![
+        Exchange::Huobi => vec![
             MarketType::Spot,
             MarketType::InverseFuture,
             MarketType::LinearSwap,
             MarketType::InverseSwap,
             // MarketType::EuropeanOption,
         ],
-        "kraken" => vec![MarketType::Spot, MarketType::InverseFuture, MarketType::InverseSwap],
-        "kucoin" => vec![
+        Exchange::Kraken => {
+            vec![MarketType::Spot, MarketType::InverseFuture, MarketType::InverseSwap]
+        }
+        Exchange::Kucoin => vec![
             MarketType::Spot,
             MarketType::LinearSwap,
             MarketType::InverseSwap,
             MarketType::InverseFuture,
         ],
-        "mxc" | "mexc" => vec![MarketType::Spot, MarketType::LinearSwap, MarketType::InverseSwap],
-        "okex" | "okx" => vec![
+        Exchange::Mexc => vec![MarketType::Spot, MarketType::LinearSwap, MarketType::InverseSwap],
+        Exchange::Okx => vec![
             MarketType::Spot,
             MarketType::LinearFuture,
             MarketType::InverseFuture,
@@ -127,8 +200,138 @@ pub fn get_market_types(exchange: &str) -> Vec<MarketType> {
             MarketType::InverseSwap,
             MarketType::EuropeanOption,
         ],
-        "zb" => vec![MarketType::Spot, MarketType::LinearSwap],
-        "zbg" => vec![MarketType::Spot, MarketType::InverseSwap, MarketType::LinearSwap],
-        _ => panic!("Unknown exchange {exchange}"),
+        Exchange::Zb => vec![MarketType::Spot, MarketType::LinearSwap],
+        Exchange::Zbg => vec![MarketType::Spot, MarketType::InverseSwap, MarketType::LinearSwap],
+    }
+}
+
+/// Infer the market type of a raw exchange symbol.
+///
+/// Returns `None` if the symbol alone is ambiguous, e.g., Binance `BTCUSDT`
+/// exists in both spot and linear swap markets. Panics if the exchange is
+/// unknown, like `get_market_types()`.
+///
+/// ```
+/// use crypto_market_type::{infer_market_type, MarketType};
+///
+/// assert_eq!(Some(MarketType::InverseSwap), infer_market_type("binance", "BTCUSD_PERP"));
+/// assert_eq!(Some(MarketType::LinearSwap), infer_market_type("okx", "BTC-USDT-SWAP"));
+/// assert_eq!(None, infer_market_type("binance", "BTCUSDT"));
+/// ```
+pub fn infer_market_type(exchange: &str, symbol: &str) -> Option<MarketType> {
+    let exchange = match exchange.parse::<Exchange>() {
+        Ok(exchange) => exchange,
+        Err(_) => panic!("Unknown exchange {exchange}"),
+    };
+    let is_option = |sep: char| {
+        let parts: Vec<&str> = symbol.split(sep).collect();
+        parts.len() >= 4 && matches!(parts[parts.len() - 1], "C" | "P")
+    };
+    let is_date = |s: &str| s.len() >= 6 && s.chars().all(|c| c.is_ascii_digit());
+    let is_base = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_uppercase());
+
+    match exchange {
+        Exchange::Bithumb | Exchange::Bitstamp | Exchange::Coinbase | Exchange::CoinbasePro => {
+            Some(MarketType::Spot)
+        }
+        Exchange::Dydx => Some(MarketType::LinearSwap),
+        Exchange::Binance => {
+            if is_option('-') {
+                Some(MarketType::EuropeanOption)
+            } else if symbol.ends_with("_PERP") {
+                Some(MarketType::InverseSwap)
+            } else if let Some((pair, date)) = symbol.split_once('_') {
+                if !is_date(date) {
+                    None
+                } else if pair.ends_with("USD") {
+                    Some(MarketType::InverseFuture)
+                } else {
+                    Some(MarketType::LinearFuture)
+                }
+            } else {
+                None
+            }
+        }
+        Exchange::Deribit => {
+            // USDC-settled instruments are named like ETH_USDC-PERPETUAL, the
+            // others are settled in coins
+            let linear = symbol.split('-').next().unwrap().contains('_');
+            if is_option('-') {
+                Some(MarketType::EuropeanOption)
+            } else if !symbol.contains('-') {
+                None // spot, e.g., BTC_USDC
+            } else if symbol.ends_with("-PERPETUAL") {
+                Some(if linear { MarketType::LinearSwap } else { MarketType::InverseSwap })
+            } else {
+                Some(if linear { MarketType::LinearFuture } else { MarketType::InverseFuture })
+            }
+        }
+        Exchange::Huobi => {
+            // inverse futures are named like BTC_CQ or BTC210625
+            let is_future = match symbol.split_once('_') {
+                Some((base, contract)) => {
+                    is_base(base) && matches!(contract, "CW" | "NW" | "CQ" | "NQ")
+                }
+                None if symbol.is_ascii() => {
+                    let (base, date) = symbol.split_at(symbol.len().saturating_sub(6));
+                    is_base(base) && is_date(date)
+                }
+                None => false,
+            };
+            if symbol.chars().any(|c| c.is_ascii_lowercase())
+                && symbol.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            {
+                Some(MarketType::Spot)
+            } else if symbol.ends_with("-USDT") {
+                Some(MarketType::LinearSwap)
+            } else if symbol.ends_with("-USD") {
+                Some(MarketType::InverseSwap)
+            } else if is_future {
+                Some(MarketType::InverseFuture)
+            } else {
+                None
+            }
+        }
+        Exchange::Kraken => {
+            if symbol.contains('/') {
+                Some(MarketType::Spot)
+            } else if symbol.starts_with("PI_") {
+                Some(MarketType::InverseSwap)
+            } else if symbol.starts_with("FI_") {
+                Some(MarketType::InverseFuture)
+            } else {
+                None
+            }
+        }
+        Exchange::Okx => {
+            let parts: Vec<&str> = symbol.split('-').collect();
+            if is_option('-') {
+                Some(MarketType::EuropeanOption)
+            } else if parts.len() == 2 {
+                Some(MarketType::Spot)
+            } else if parts.len() == 3 {
+                let inverse = parts[1] == "USD";
+                match (parts[2] == "SWAP", inverse) {
+                    (true, true) => Some(MarketType::InverseSwap),
+                    (true, false) => Some(MarketType::LinearSwap),
+                    (false, true) => Some(MarketType::InverseFuture),
+                    (false, false) => Some(MarketType::LinearFuture),
+                }
+            } else {
+                None
+            }
+        }
+        // not supported yet
+        Exchange::Bitfinex
+        | Exchange::Bitget
+        | Exchange::Bitmex
+        | Exchange::Bitz
+        | Exchange::Bybit
+        | Exchange::Ftx
+        | Exchange::Gate
+        | Exchange::Kucoin
+        | Exchange::Mexc
+        | Exchange::Zb
+        | Exchange::Zbg => None,
     }
 }
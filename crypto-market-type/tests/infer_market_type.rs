@@ -0,0 +1,98 @@
+use crypto_market_type::{infer_market_type, MarketType};
+
+#[test]
+fn binance() {
+    assert_eq!(Some(MarketType::InverseSwap), infer_market_type("binance", "BTCUSD_PERP"));
+    assert_eq!(Some(MarketType::InverseFuture), infer_market_type("binance", "BTCUSD_230630"));
+    assert_eq!(Some(MarketType::LinearFuture), infer_market_type("binance", "BTCUSDT_230630"));
+    assert_eq!(
+        Some(MarketType::EuropeanOption),
+        infer_market_type("binance", "BTC-230630-30000-C")
+    );
+    // spot and linear swap markets share symbols
+    assert_eq!(None, infer_market_type("binance", "BTCUSDT"));
+    assert_eq!(None, infer_market_type("binance", "BTCUSD_NEXT"));
+}
+
+#[test]
+fn deribit() {
+    assert_eq!(Some(MarketType::InverseSwap), infer_market_type("deribit", "BTC-PERPETUAL"));
+    assert_eq!(Some(MarketType::InverseFuture), infer_market_type("deribit", "BTC-30JUN23"));
+    assert_eq!(
+        Some(MarketType::EuropeanOption),
+        infer_market_type("deribit", "BTC-30JUN23-30000-C")
+    );
+    // USDC-settled
+    assert_eq!(Some(MarketType::LinearSwap), infer_market_type("deribit", "ETH_USDC-PERPETUAL"));
+    assert_eq!(Some(MarketType::LinearFuture), infer_market_type("deribit", "BTC_USDC-30JUN23"));
+    assert_eq!(
+        Some(MarketType::EuropeanOption),
+        infer_market_type("deribit", "XRP_USDC-30JUN23-0d625-C")
+    );
+
+    assert_eq!(None, infer_market_type("deribit", "BTC_USDC"));
+    assert_eq!(None, infer_market_type("deribit", "BTC"));
+}
+
+#[test]
+fn huobi() {
+    assert_eq!(Some(MarketType::Spot), infer_market_type("huobi", "btcusdt"));
+    assert_eq!(Some(MarketType::LinearSwap), infer_market_type("huobi", "BTC-USDT"));
+    assert_eq!(Some(MarketType::InverseSwap), infer_market_type("huobi", "BTC-USD"));
+    assert_eq!(Some(MarketType::InverseFuture), infer_market_type("huobi", "BTC_CQ"));
+    assert_eq!(Some(MarketType::InverseFuture), infer_market_type("huobi", "BTC_NW"));
+    assert_eq!(Some(MarketType::InverseFuture), infer_market_type("huobi", "BTC210625"));
+
+    assert_eq!(None, infer_market_type("huobi", ""));
+    assert_eq!(None, infer_market_type("huobi", "BTCUSDT"));
+    assert_eq!(None, infer_market_type("huobi", "BTC_XX"));
+    assert_eq!(None, infer_market_type("huobi", "btc_CQ"));
+    assert_eq!(None, infer_market_type("huobi", "BTC2106"));
+    assert_eq!(None, infer_market_type("huobi", "210625"));
+    assert_eq!(None, infer_market_type("huobi", "BTC€210625"));
+}
+
+#[test]
+fn kraken() {
+    assert_eq!(Some(MarketType::Spot), infer_market_type("kraken", "XBT/USD"));
+    assert_eq!(Some(MarketType::InverseSwap), infer_market_type("kraken", "PI_XBTUSD"));
+    assert_eq!(Some(MarketType::InverseFuture), infer_market_type("kraken", "FI_XBTUSD_230630"));
+    assert_eq!(None, infer_market_type("kraken", "PF_XBTUSD"));
+}
+
+#[test]
+fn okx() {
+    for exchange in ["okx", "okex"] {
+        assert_eq!(Some(MarketType::Spot), infer_market_type(exchange, "BTC-USDT"));
+        assert_eq!(Some(MarketType::LinearSwap), infer_market_type(exchange, "BTC-USDT-SWAP"));
+        assert_eq!(Some(MarketType::InverseSwap), infer_market_type(exchange, "BTC-USD-SWAP"));
+        assert_eq!(Some(MarketType::LinearFuture), infer_market_type(exchange, "BTC-USDT-230630"));
+        assert_eq!(Some(MarketType::InverseFuture), infer_market_type(exchange, "BTC-USD-230630"));
+        assert_eq!(
+            Some(MarketType::EuropeanOption),
+            infer_market_type(exchange, "BTC-USD-230630-30000-C")
+        );
+        assert_eq!(None, infer_market_type(exchange, "BTCUSDT"));
+    }
+}
+
+#[test]
+fn single_market_exchanges() {
+    for exchange in ["bithumb", "bitstamp", "coinbase", "coinbase_pro"] {
+        assert_eq!(Some(MarketType::Spot), infer_market_type(exchange, "BTC-USDT"));
+    }
+    assert_eq!(Some(MarketType::LinearSwap), infer_market_type("dydx", "BTC-USD"));
+}
+
+#[test]
+fn unsupported_exchanges() {
+    for exchange in ["bitmex", "mexc", "mxc"] {
+        assert_eq!(None, infer_market_type(exchange, "BTC_USDT"));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Unknown exchange binanace")]
+fn unknown_exchange() {
+    infer_market_type("binanace", "BTCUSD_PERP");
+}
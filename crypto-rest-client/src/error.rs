@@ -1,13 +1,51 @@
-use std::{error::Error as StdError, fmt};
+use std::{error::Error as StdError, fmt, time::Duration};
 
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
+/// Rate limit status reported by an exchange.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// Request weight used in the current window, e.g., Binance
+    /// `X-MBX-USED-WEIGHT-1M`
+    pub used_weight: Option<u64>,
+    /// Max request weight allowed in the current window, if known
+    pub limit: Option<u64>,
+    /// How long to wait before sending the next request, from the
+    /// `Retry-After` header
+    pub retry_after: Option<Duration>,
+}
+
 #[derive(Debug)]
-pub struct Error(pub String);
+pub enum Error {
+    /// Generic error with a message.
+    Msg(String),
+    /// The exchange rejected the request because of rate limits, e.g., HTTP
+    /// 429, callers should slow down instead of retrying immediately.
+    RateLimited(RateLimitInfo),
+    /// The IP address was banned for repeatedly violating rate limits, e.g.,
+    /// Binance HTTP 418, `retry_after` tells how long the ban lasts.
+    IpBanned(RateLimitInfo),
+    /// The exchange is not available in the region of the IP address, e.g.,
+    /// Binance HTTP 451, retrying won't help, use a server in another region.
+    GeoBlocked(String),
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            Error::Msg(msg) => write!(f, "{msg}"),
+            Error::RateLimited(info) => write!(
+                f,
+                "Rate limited, used_weight: {:?}, limit: {:?}, retry_after: {:?}",
+                info.used_weight, info.limit, info.retry_after
+            ),
+            Error::IpBanned(info) => write!(
+                f,
+                "IP banned, used_weight: {:?}, retry_after: {:?}",
+                info.used_weight, info.retry_after
+            ),
+            Error::GeoBlocked(msg) => write!(f, "Geo-blocked, {msg}"),
+        }
     }
 }
 
@@ -15,12 +53,12 @@ impl StdError for Error {}
 
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
-        Error(err.to_string())
+        Error::Msg(err.to_string())
     }
 }
 
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
-        Error(err.to_string())
+        Error::Msg(err.to_string())
     }
 }
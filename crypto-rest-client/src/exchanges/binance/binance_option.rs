@@ -1,8 +1,9 @@
-use super::{super::utils::http_get, utils::*};
+use super::utils::*;
 use crate::error::Result;
 use std::collections::BTreeMap;
 
 const BASE_URL: &str = "https://vapi.binance.com";
+const WEIGHT_LIMIT: Option<u64> = None;
 
 /// Binance Option market.
 ///
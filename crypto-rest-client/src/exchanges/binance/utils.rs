@@ -1,8 +1,10 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
 
-use crate::error::{Error, Result};
+use super::super::utils::http_get_raw;
+use crate::error::{Error, RateLimitInfo, Result};
 
 use once_cell::sync::Lazy;
+use reqwest::{header::HeaderMap, StatusCode};
 use regex::Regex;
 use serde_json::Value;
 
@@ -23,7 +25,7 @@ pub(super) fn check_code_in_body(resp: String) -> Result<String> {
     match obj.unwrap().get("code") {
         Some(code) => {
             if code.as_i64().unwrap() != 0 {
-                Err(Error(resp))
+                Err(Error::Msg(resp))
             } else {
                 Ok(resp)
             }
@@ -32,6 +34,53 @@ pub(super) fn check_code_in_body(resp: String) -> Result<String> {
     }
 }
 
+// Extracts rate limit status from Binance response headers.
+//
+// See <https://binance-docs.github.io/apidocs/spot/en/#limits>
+pub(super) fn extract_rate_limit_info(
+    headers: &HeaderMap,
+    weight_limit: Option<u64>,
+) -> RateLimitInfo {
+    let parse_header = |name: &str| -> Option<u64> {
+        headers.get(name).and_then(|v| v.to_str().ok()).and_then(|v| v.trim().parse::<u64>().ok())
+    };
+    RateLimitInfo {
+        used_weight: parse_header("x-mbx-used-weight-1m")
+            .or_else(|| parse_header("x-mbx-used-weight")),
+        limit: weight_limit,
+        retry_after: parse_header("retry-after").map(Duration::from_secs),
+    }
+}
+
+// Sends a GET request to Binance, HTTP 429 is converted to
+// `Error::RateLimited`, 418 to `Error::IpBanned` and 451 to `Error::GeoBlocked`.
+//
+// See <https://binance-docs.github.io/apidocs/spot/en/#general-api-information>
+pub(super) fn http_get_binance(
+    url: &str,
+    params: &BTreeMap<String, String>,
+    weight_limit: Option<u64>,
+) -> Result<String> {
+    let response = http_get_raw(url, params)?;
+    match response.status() {
+        StatusCode::TOO_MANY_REQUESTS => {
+            let info = extract_rate_limit_info(response.headers(), weight_limit);
+            return Err(Error::RateLimited(info));
+        }
+        StatusCode::IM_A_TEAPOT => {
+            let info = extract_rate_limit_info(response.headers(), weight_limit);
+            return Err(Error::IpBanned(info));
+        }
+        StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS => {
+            let body = response.text()?;
+            return Err(Error::GeoBlocked(format!("{url} returned HTTP 451, {body}")));
+        }
+        _ => (),
+    }
+    let text = response.error_for_status()?.text()?;
+    check_code_in_body(text)
+}
+
 macro_rules! gen_api_binance {
     ( $path:expr$(, $param_name:ident )* ) => {
         {
@@ -42,11 +91,98 @@ macro_rules! gen_api_binance {
                     params.insert(stringify!($param_name).to_string(), param_name.to_string());
                 }
             )*
-            let ret = http_get(format!("{}{}",BASE_URL, $path).as_str(), &params);
-            match ret {
-                Ok(resp) => check_code_in_body(resp),
-                Err(_) => ret,
+            http_get_binance(format!("{}{}",BASE_URL, $path).as_str(), &params, WEIGHT_LIMIT)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        io::{Read, Write},
+        net::TcpListener,
+        time::Duration,
+    };
+
+    use crate::error::{Error, RateLimitInfo};
+
+    // Serves one request with the given response head and body
+    fn mock_server(
+        head: &'static str,
+        body: &'static str,
+    ) -> (String, std::thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).unwrap();
+            let resp = format!(
+                "{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                head,
+                body.len(),
+                body
+            );
+            stream.write_all(resp.as_bytes()).unwrap();
+        });
+        (format!("http://{addr}/api/v3/depth"), server)
+    }
+
+    #[test]
+    fn rate_limited_with_retry_after() {
+        let (url, server) = mock_server(
+            "HTTP/1.1 429 Too Many Requests\r\n\
+             Retry-After: 30\r\n\
+             X-MBX-USED-WEIGHT-1M: 1250\r\n",
+            r#"{"code":-1003,"msg":"Too many requests."}"#,
+        );
+        let ret = super::http_get_binance(&url, &BTreeMap::new(), Some(1200));
+        server.join().unwrap();
+
+        match ret {
+            Err(Error::RateLimited(info)) => assert_eq!(
+                RateLimitInfo {
+                    used_weight: Some(1250),
+                    limit: Some(1200),
+                    retry_after: Some(Duration::from_secs(30)),
+                },
+                info
+            ),
+            _ => panic!("Expected Error::RateLimited, got {ret:?}"),
+        }
+    }
+
+    #[test]
+    fn ip_banned() {
+        let (url, server) = mock_server(
+            "HTTP/1.1 418 I'm a teapot\r\n\
+             Retry-After: 120\r\n",
+            r#"{"code":-1003,"msg":"Way too many requests; IP banned."}"#,
+        );
+        let ret = super::http_get_binance(&url, &BTreeMap::new(), Some(1200));
+        server.join().unwrap();
+
+        match ret {
+            Err(Error::IpBanned(info)) => {
+                assert_eq!(Some(Duration::from_secs(120)), info.retry_after)
             }
+            _ => panic!("Expected Error::IpBanned, got {ret:?}"),
+        }
+    }
+
+    #[test]
+    fn geo_blocked() {
+        let (url, server) = mock_server(
+            "HTTP/1.1 451 Unavailable For Legal Reasons\r\n",
+            r#"{"code":0,"msg":"Service unavailable from a restricted location."}"#,
+        );
+        let ret = super::http_get_binance(&url, &BTreeMap::new(), None);
+        server.join().unwrap();
+
+        match ret {
+            Err(Error::GeoBlocked(msg)) => assert!(msg.contains("restricted location")),
+            _ => panic!("Expected Error::GeoBlocked, got {ret:?}"),
         }
     }
 }
@@ -31,7 +31,7 @@ impl BitzSwapRestClient {
     pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
         let symbol_id_map = get_symbol_id_map()?;
         if !symbol_id_map.contains_key(symbol) {
-            return Err(Error(format!("Can NOT find contractId for the pair {symbol}")));
+            return Err(Error::Msg(format!("Can NOT find contractId for the pair {symbol}")));
         }
         let contract_id = symbol_id_map.get(symbol).unwrap();
         gen_api!(format!("/V2/Market/getContractOrderBook?contractId={contract_id}&depth=100"))
@@ -44,7 +44,7 @@ impl BitzSwapRestClient {
         if let Some(symbol) = symbol {
             let symbol_id_map = get_symbol_id_map()?;
             if !symbol_id_map.contains_key(symbol) {
-                return Err(Error(format!("Can NOT find contractId for the pair {symbol}")));
+                return Err(Error::Msg(format!("Can NOT find contractId for the pair {symbol}")));
             }
             let contract_id = symbol_id_map.get(symbol).unwrap();
             gen_api!(format!("/V2/Market/getContractTickers?contractId={contract_id}"))
@@ -79,7 +79,7 @@ fn get_symbol_id_map() -> Result<HashMap<String, String>> {
     let txt = http_get("https://apiv2.bitz.com/Market/getContractCoin", &params)?;
     let resp = serde_json::from_str::<Response>(&txt)?;
     if resp.status != 200 {
-        return Err(Error(txt));
+        return Err(Error::Msg(txt));
     }
 
     let mut symbol_id_map = HashMap::<String, String>::new();
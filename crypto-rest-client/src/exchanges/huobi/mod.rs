@@ -26,7 +26,7 @@ pub(crate) fn fetch_l2_snapshot(market_type: MarketType, symbol: &str) -> Result
     match func(symbol) {
         Ok(msg) => {
             if msg == r#"{"status": "maintain"}"# {
-                Err(Error(msg))
+                Err(Error::Msg(msg))
             } else {
                 Ok(msg)
             }
@@ -49,7 +49,7 @@ pub(crate) fn fetch_open_interest(market_type: MarketType, symbol: Option<&str>)
     match func(symbol) {
         Ok(msg) => {
             if msg == r#"{"status": "maintain"}"# {
-                Err(Error(msg))
+                Err(Error::Msg(msg))
             } else {
                 Ok(msg)
             }
@@ -1,7 +1,7 @@
 mod error;
 mod exchanges;
 
-pub use error::Error;
+pub use error::{Error, RateLimitInfo};
 pub use exchanges::{
     binance::{
         binance_inverse::BinanceInverseRestClient, binance_linear::BinanceLinearRestClient,
@@ -136,7 +136,8 @@ pub fn fetch_long_short_ratio(
 /// Fetch level2 orderbook snapshot.
 ///
 /// `retry` None means no retry; Some(0) means retry unlimited times; Some(n)
-/// means retry n times.
+/// means retry n times. `Error::RateLimited`, `Error::IpBanned` and
+/// `Error::GeoBlocked` are returned immediately without retrying.
 pub fn fetch_l2_snapshot(
     exchange: &str,
     market_type: MarketType,
@@ -149,7 +150,8 @@ pub fn fetch_l2_snapshot(
 /// Fetch level3 orderbook snapshot.
 ///
 /// `retry` None means no retry; Some(0) means retry unlimited times; Some(n)
-/// means retry n times.
+/// means retry n times. `Error::RateLimited`, `Error::IpBanned` and
+/// `Error::GeoBlocked` are returned immediately without retrying.
 pub fn fetch_l3_snapshot(
     exchange: &str,
     market_type: MarketType,
@@ -181,6 +183,10 @@ fn retriable(
         let resp = crawl_func(exchange, market_type, symbol);
         match resp {
             Ok(msg) => return Ok(msg),
+            // Surface rate limits to callers so that they can slow down globally
+            Err(err @ Error::RateLimited(_)) => return Err(err),
+            // Retrying makes a ban longer and can't lift a geo-block
+            Err(err @ (Error::IpBanned(_) | Error::GeoBlocked(_))) => return Err(err),
             Err(err) => {
                 let current_timestamp =
                     SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis()
@@ -196,7 +202,7 @@ fn retriable(
                     (backoff_factor * cooldown_time).as_millis()
                 );
                 std::thread::sleep(backoff_factor * cooldown_time);
-                if err.0.contains("429") {
+                if err.to_string().contains("429") {
                     backoff_factor += 1;
                 } else {
                     // Handle 403, 418, etc.
@@ -205,7 +211,7 @@ fn retriable(
             }
         }
     }
-    Err(Error(format!(
+    Err(Error::Msg(format!(
         "Failed {exchange} {market_type} {symbol} after retrying {retry_count} times"
     )))
 }
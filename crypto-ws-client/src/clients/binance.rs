@@ -5,12 +5,13 @@ use tokio_tungstenite::tungstenite::Message;
 
 use crate::{
     common::{
+        candlestick::{interval_names, to_interval_str, BINANCE_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         utils::ensure_frame_size,
         ws_client_internal::WSClientInternal,
     },
-    WSClient,
+    BboCadence, Channel, FromWSClientBuilder, WSClient, WSClientBuilder,
 };
 use log::*;
 use serde_json::Value;
@@ -20,10 +21,11 @@ pub(crate) const EXCHANGE_NAME: &str = "binance";
 const SPOT_WEBSOCKET_URL: &str = "wss://stream.binance.com:9443/stream";
 const LINEAR_WEBSOCKET_URL: &str = "wss://fstream.binance.com/stream";
 const INVERSE_WEBSOCKET_URL: &str = "wss://dstream.binance.com/stream";
+const OPTION_WEBSOCKET_URL: &str = "wss://stream.opsnest.com/stream";
 
 // the websocket message size should not exceed 4096 bytes, otherwise
 // you'll get `code: 3001, reason: illegal request`
-const WS_FRAME_SIZE: usize = 4096;
+pub(super) const WS_FRAME_SIZE: usize = 4096;
 
 // WebSocket connections have a limit of 5 incoming messages per second.
 //
@@ -35,6 +37,68 @@ const WS_FRAME_SIZE: usize = 4096;
 const UPLINK_LIMIT: (NonZeroU32, std::time::Duration) =
     (nonzero!(5u32), std::time::Duration::from_secs(1));
 
+// Endpoints and stream names of all Binance markets are built here, keyed on
+// the market type, 'S' for Spot, 'L' for USDT-margined, 'I' for COIN-margined
+// and 'O' for Option.
+pub(super) fn websocket_url(market_type: char) -> &'static str {
+    match market_type {
+        'S' => SPOT_WEBSOCKET_URL,
+        'L' => LINEAR_WEBSOCKET_URL,
+        'I' => INVERSE_WEBSOCKET_URL,
+        'O' => OPTION_WEBSOCKET_URL,
+        _ => panic!("Unknown market type {market_type}"),
+    }
+}
+
+// Panics if `url` is the endpoint of another Binance market, otherwise
+// subscriptions succeed but no data arrives, e.g., a COIN-margined symbol
+// sent to `fstream.binance.com`. Unknown hosts such as proxies are allowed.
+pub(super) fn check_url(market_type: char, url: &str) {
+    fn host(url: &str) -> &str {
+        url.split("://").last().unwrap().split(['/', ':']).next().unwrap()
+    }
+    let url_market_type =
+        ['S', 'L', 'I', 'O'].into_iter().find(|x| host(websocket_url(*x)) == host(url));
+    if let Some(url_market_type) = url_market_type {
+        assert_eq!(
+            market_type, url_market_type,
+            "{url} is the endpoint of market type {url_market_type}, not {market_type}"
+        );
+    }
+}
+
+// Option symbols such as `BTC-220429-50000-C` are kept as is, symbols of
+// other markets are lowercase, e.g., `btcusdt@aggTrade`
+pub(super) fn stream_name(market_type: char, channel: &str, symbol: &str) -> String {
+    if market_type == 'O' {
+        format!("{symbol}@{channel}")
+    } else {
+        format!("{}@{}", symbol.to_lowercase(), channel)
+    }
+}
+
+/// Max number of streams in one SUBSCRIBE command.
+pub(super) fn max_streams_per_command(market_type: char) -> usize {
+    if market_type == 'S' {
+        // https://binance-docs.github.io/apidocs/spot/en/#websocket-limits
+        1024
+    } else {
+        // https://binance-docs.github.io/apidocs/futures/en/#websocket-market-streams
+        // https://binance-docs.github.io/apidocs/delivery/en/#websocket-market-streams
+        200
+    }
+}
+
+// Builds a SUBSCRIBE or UNSUBSCRIBE command, stream names of all-market
+// streams such as `!ticker@arr` don't have a symbol
+pub(super) fn streams_to_command(streams: &[String], subscribe: bool) -> String {
+    format!(
+        r#"{{"id":9527,"method":"{}","params":{}}}"#,
+        if subscribe { "SUBSCRIBE" } else { "UNSUBSCRIBE" },
+        serde_json::to_string(streams).unwrap()
+    )
+}
+
 // Internal unified client
 pub struct BinanceWSClient<const MARKET_TYPE: char> {
     client: WSClientInternal<BinanceMessageHandler>,
@@ -61,20 +125,42 @@ pub type BinanceLinearWSClient = BinanceWSClient<'L'>;
 
 impl<const MARKET_TYPE: char> BinanceWSClient<MARKET_TYPE> {
     pub async fn new(tx: std::sync::mpsc::Sender<String>, url: Option<&str>) -> Self {
-        let real_url = match url {
+        Self::from_builder(WSClientBuilder::with_url(url), tx).await
+    }
+
+    /// Creates a client which sends at most `messages_per_second` uplink
+    /// messages per second.
+    ///
+    /// Outgoing messages are queued and paced by a token bucket, so a
+    /// `subscribe()` with hundreds of symbols doesn't get the connection
+    /// dropped. `new()` uses Binance's limit of 5 messages per second.
+    pub async fn new_with_rate_limit(
+        tx: std::sync::mpsc::Sender<String>,
+        url: Option<&str>,
+        messages_per_second: NonZeroU32,
+    ) -> Self {
+        let builder =
+            WSClientBuilder::with_url(url).rate_limit(messages_per_second, UPLINK_LIMIT.1);
+        Self::from_builder(builder, tx).await
+    }
+
+    async fn subscribe_channel(&self, channel: &str, symbols: &[String]) {
+        let topics = symbols
+            .iter()
+            .map(|symbol| (channel.to_string(), symbol.to_string()))
+            .collect::<Vec<(String, String)>>();
+        self.subscribe(&topics).await;
+    }
+}
+
+#[async_trait]
+impl<const MARKET_TYPE: char> FromWSClientBuilder for BinanceWSClient<MARKET_TYPE> {
+    async fn from_builder(builder: WSClientBuilder, tx: std::sync::mpsc::Sender<String>) -> Self {
+        let real_url = match builder.url.as_deref() {
             Some(endpoint) => endpoint,
-            None => {
-                if MARKET_TYPE == 'S' {
-                    SPOT_WEBSOCKET_URL
-                } else if MARKET_TYPE == 'I' {
-                    INVERSE_WEBSOCKET_URL
-                } else if MARKET_TYPE == 'L' {
-                    LINEAR_WEBSOCKET_URL
-                } else {
-                    panic!("Unknown market type {MARKET_TYPE}");
-                }
-            }
+            None => websocket_url(MARKET_TYPE),
         };
+        check_url(MARKET_TYPE, real_url);
         BinanceWSClient {
             client: WSClientInternal::connect(
                 EXCHANGE_NAME,
@@ -82,6 +168,7 @@ impl<const MARKET_TYPE: char> BinanceWSClient<MARKET_TYPE> {
                 BinanceMessageHandler {},
                 Some(UPLINK_LIMIT),
                 tx,
+                &builder,
             )
             .await,
             translator: BinanceCommandTranslator { market_type: MARKET_TYPE },
@@ -90,29 +177,32 @@ impl<const MARKET_TYPE: char> BinanceWSClient<MARKET_TYPE> {
 }
 
 #[async_trait]
-impl<const URL: char> WSClient for BinanceWSClient<URL> {
+impl<const MARKET_TYPE: char> WSClient for BinanceWSClient<MARKET_TYPE> {
     async fn subscribe_trade(&self, symbols: &[String]) {
-        let topics = symbols
-            .iter()
-            .map(|symbol| ("aggTrade".to_string(), symbol.to_string()))
-            .collect::<Vec<(String, String)>>();
-        self.subscribe(&topics).await;
+        self.subscribe_channel("aggTrade", symbols).await;
     }
 
     async fn subscribe_orderbook(&self, symbols: &[String]) {
-        let topics = symbols
-            .iter()
-            .map(|symbol| ("depth@100ms".to_string(), symbol.to_string()))
-            .collect::<Vec<(String, String)>>();
-        self.subscribe(&topics).await;
+        self.subscribe_channel("depth@100ms", symbols).await;
     }
 
     async fn subscribe_orderbook_topk(&self, symbols: &[String]) {
-        let topics = symbols
-            .iter()
-            .map(|symbol| ("depth20".to_string(), symbol.to_string()))
-            .collect::<Vec<(String, String)>>();
-        self.subscribe(&topics).await;
+        self.subscribe_channel("depth20", symbols).await;
+    }
+
+    async fn subscribe_orderbook_topk_with_depth(&self, symbols: &[String], k: usize) {
+        let channel = BinanceCommandTranslator::to_l2_raw_channel(MARKET_TYPE, Some(k), None);
+        self.subscribe_channel(&channel, symbols).await;
+    }
+
+    async fn subscribe_l2(
+        &self,
+        symbols: &[String],
+        depth: Option<usize>,
+        interval_ms: Option<u64>,
+    ) {
+        let channel = BinanceCommandTranslator::to_l2_raw_channel(MARKET_TYPE, depth, interval_ms);
+        self.subscribe_channel(&channel, symbols).await;
     }
 
     async fn subscribe_l3_orderbook(&self, _symbols: &[String]) {
@@ -120,43 +210,119 @@ impl<const URL: char> WSClient for BinanceWSClient<URL> {
     }
 
     async fn subscribe_ticker(&self, symbols: &[String]) {
-        let topics = symbols
-            .iter()
-            .map(|symbol| ("ticker".to_string(), symbol.to_string()))
-            .collect::<Vec<(String, String)>>();
-        self.subscribe(&topics).await;
+        self.subscribe_channel("ticker", symbols).await;
     }
 
     async fn subscribe_bbo(&self, symbols: &[String]) {
-        let topics = symbols
-            .iter()
-            .map(|symbol| ("bookTicker".to_string(), symbol.to_string()))
-            .collect::<Vec<(String, String)>>();
-        self.subscribe(&topics).await;
+        self.subscribe_channel("bookTicker", symbols).await;
+    }
+
+    async fn subscribe_bbo_with_cadence(&self, symbols: &[String], cadence: BboCadence) {
+        match cadence {
+            BboCadence::TickByTick => self.subscribe_bbo(symbols).await,
+            BboCadence::Throttled => panic!("{EXCHANGE_NAME} bookTicker is tick-by-tick only"),
+        }
     }
 
     async fn subscribe_candlestick(&self, symbol_interval_list: &[(String, usize)]) {
+        // translate first, an unsupported interval panics before it is tracked
         let commands =
             self.translator.translate_to_candlestick_commands(true, symbol_interval_list);
-        self.client.send(&commands).await;
+        self.client.add_candlesticks(symbol_interval_list);
+        self.client.send_tracked(&commands).await;
+    }
+
+    async fn subscribe_candlestick_raw(&self, symbol_interval_list: &[(String, String)]) {
+        let topics = symbol_interval_list
+            .iter()
+            .map(|(symbol, interval)| {
+                let channel = BinanceCommandTranslator::to_candlestick_raw_channel_str(interval);
+                (channel, symbol.to_lowercase())
+            })
+            .collect::<Vec<(String, String)>>();
+        self.subscribe(&topics).await;
+    }
+
+    async fn subscribe_index_price(&self, symbols: &[String]) {
+        if MARKET_TYPE != 'I' {
+            panic!("{EXCHANGE_NAME} has indexPrice in COIN-margined markets only");
+        }
+        self.subscribe_channel("indexPrice@1s", symbols).await;
+    }
+
+    async fn subscribe_mark_price(&self, symbols: &[String]) {
+        if MARKET_TYPE == 'S' {
+            panic!("{EXCHANGE_NAME} spot market does NOT have markPrice");
+        }
+        self.subscribe_channel("markPrice@1s", symbols).await;
+    }
+
+    async fn subscribe_funding_rate(&self, symbols: &[String]) {
+        if MARKET_TYPE == 'S' {
+            panic!("{EXCHANGE_NAME} spot market does NOT have funding rates");
+        }
+        self.subscribe_channel("markPrice@1s", symbols).await;
+    }
+
+    async fn subscribe_all_bbo(&self) {
+        if MARKET_TYPE == 'S' {
+            panic!("{EXCHANGE_NAME} spot market has removed !bookTicker since December 7, 2022");
+        }
+        self.client.subscribe_raw(&[streams_to_command(&["!bookTicker".to_string()], true)]).await;
+    }
+
+    async fn subscribe_all_ticker(&self) {
+        self.client.subscribe_raw(&[streams_to_command(&["!ticker@arr".to_string()], true)]).await;
     }
 
     async fn subscribe(&self, topics: &[(String, String)]) {
+        self.client.add_subscriptions(topics);
         let commands = self.translator.translate_to_commands(true, topics);
-        self.client.send(&commands).await;
+        self.client.send_tracked(&commands).await;
+    }
+
+    async fn subscribe_typed(&self, topics: &[(Channel, String)]) {
+        let topics = topics
+            .iter()
+            .map(|(channel, symbol)| {
+                (BinanceCommandTranslator::to_raw_channel(channel), symbol.to_string())
+            })
+            .collect::<Vec<(String, String)>>();
+        self.subscribe(&topics).await;
     }
 
     async fn unsubscribe(&self, topics: &[(String, String)]) {
+        self.client.remove_subscriptions(topics);
         let commands = self.translator.translate_to_commands(false, topics);
-        self.client.send(&commands).await;
+        self.client.send_tracked(&commands).await;
+    }
+
+    fn subscriptions(&self) -> Vec<(String, String)> {
+        self.client.subscriptions()
+    }
+
+    fn candlestick_subscriptions(&self) -> Vec<(String, usize)> {
+        self.client.candlesticks()
     }
 
     async fn send(&self, commands: &[String]) {
         self.client.send(commands).await;
     }
 
-    async fn run(&self) {
-        self.client.run().await;
+    async fn run(&self, duration: Option<std::time::Duration>) -> Result<(), crate::WSError> {
+        self.client.run(duration, &self.translator).await
+    }
+
+    fn connection_info(&self) -> crate::ConnectionInfo {
+        self.client.connection_info()
+    }
+
+    fn dropped_messages(&self) -> u64 {
+        self.client.dropped_messages()
+    }
+
+    fn metrics(&self) -> crate::WSClientMetrics {
+        self.client.metrics()
     }
 
     async fn close(&self) {
@@ -170,39 +336,68 @@ struct BinanceCommandTranslator {
 }
 
 impl BinanceCommandTranslator {
-    fn topics_to_command(topics: &[(String, String)], subscribe: bool) -> String {
-        let raw_topics = topics
+    fn topics_to_command(&self, topics: &[(String, String)], subscribe: bool) -> String {
+        let streams = topics
             .iter()
-            .map(|(topic, symbol)| format!("{}@{}", symbol.to_lowercase(), topic))
+            .map(|(channel, symbol)| stream_name(self.market_type, channel, symbol))
             .collect::<Vec<String>>();
-        format!(
-            r#"{{"id":9527,"method":"{}","params":{}}}"#,
-            if subscribe { "SUBSCRIBE" } else { "UNSUBSCRIBE" },
-            serde_json::to_string(&raw_topics).unwrap()
-        )
+        streams_to_command(&streams, subscribe)
+    }
+
+    // Partial book depth streams have 5, 10 or 20 levels, diff depth streams
+    // have no depth. Spot streams update every 1000ms or 100ms, futures every
+    // 250ms, 500ms or 100ms, the suffix is omitted for the default interval.
+    //
+    // see https://binance-docs.github.io/apidocs/spot/en/#partial-book-depth-streams
+    // and https://binance-docs.github.io/apidocs/futures/en/#partial-book-depth-streams
+    fn to_l2_raw_channel(
+        market_type: char,
+        depth: Option<usize>,
+        interval_ms: Option<u64>,
+    ) -> String {
+        let depth_str = match depth {
+            Some(depth @ (5 | 10 | 20)) => depth.to_string(),
+            Some(depth) => panic!("Binance has depth levels 5, 10 and 20, got {depth}"),
+            None => "".to_string(),
+        };
+        let (default_interval, intervals) =
+            if market_type == 'S' { (1000, vec![100, 1000]) } else { (250, vec![100, 250, 500]) };
+        let interval_str = match interval_ms {
+            Some(interval) if interval == default_interval => "".to_string(),
+            Some(interval) if intervals.contains(&interval) => format!("@{interval}ms"),
+            Some(interval) => {
+                panic!("Binance has update intervals {intervals:?} milliseconds, got {interval}")
+            }
+            None => "".to_string(),
+        };
+        format!("depth{depth_str}{interval_str}")
     }
 
     // see https://binance-docs.github.io/apidocs/futures/en/#kline-candlestick-streams
     fn to_candlestick_raw_channel(interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1m",
-            180 => "3m",
-            300 => "5m",
-            900 => "15m",
-            1800 => "30m",
-            3600 => "1h",
-            7200 => "2h",
-            14400 => "4h",
-            21600 => "6h",
-            28800 => "8h",
-            43200 => "12h",
-            86400 => "1d",
-            259200 => "3d",
-            604800 => "1w",
-            2592000 => "1M",
-            _ => panic!("Binance has intervals 1m,3m,5m,15m,30m,1h,2h,4h,6h,8h,12h,1d,3d,1w,1M"),
-        };
-        format!("kline_{interval_str}")
+        format!("kline_{}", to_interval_str(EXCHANGE_NAME, BINANCE_INTERVALS, interval))
+    }
+
+    // `interval` is a native interval string, e.g., `1M`, which is case
+    // sensitive because `1m` is one minute
+    fn to_candlestick_raw_channel_str(interval: &str) -> String {
+        if BINANCE_INTERVALS.iter().any(|(_, interval_str)| *interval_str == interval) {
+            format!("kline_{interval}")
+        } else {
+            panic!("Binance has intervals {}", interval_names(BINANCE_INTERVALS))
+        }
+    }
+
+    fn to_raw_channel(channel: &Channel) -> String {
+        match channel {
+            Channel::Trade => "trade".to_string(),
+            Channel::AggTrade => "aggTrade".to_string(),
+            Channel::OrderBook => "depth@100ms".to_string(),
+            Channel::OrderBookTopK => "depth20".to_string(),
+            Channel::BookTicker => "bookTicker".to_string(),
+            Channel::Ticker => "ticker".to_string(),
+            Channel::Kline(interval) => Self::to_candlestick_raw_channel(*interval),
+        }
     }
 }
 
@@ -247,20 +442,12 @@ impl MessageHandler for BinanceMessageHandler {
 
 impl CommandTranslator for BinanceCommandTranslator {
     fn translate_to_commands(&self, subscribe: bool, topics: &[(String, String)]) -> Vec<String> {
-        let max_num_topics = if self.market_type == 'S' {
-            // https://binance-docs.github.io/apidocs/spot/en/#websocket-limits
-            1024
-        } else {
-            // https://binance-docs.github.io/apidocs/futures/en/#websocket-market-streams
-            // https://binance-docs.github.io/apidocs/delivery/en/#websocket-market-streams
-            200
-        };
         ensure_frame_size(
             topics,
             subscribe,
-            Self::topics_to_command,
+            |topics, subscribe| self.topics_to_command(topics, subscribe),
             WS_FRAME_SIZE,
-            Some(max_num_topics),
+            Some(max_streams_per_command(self.market_type)),
         )
     }
 
@@ -282,7 +469,10 @@ impl CommandTranslator for BinanceCommandTranslator {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::command_translator::CommandTranslator;
+    use crate::common::{
+        command_translator::CommandTranslator,
+        message_handler::{MessageHandler, MiscMessage},
+    };
 
     #[test]
     fn test_one_topic() {
@@ -297,6 +487,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_all_market_channel() {
+        assert_eq!(
+            r#"{"id":9527,"method":"SUBSCRIBE","params":["!ticker@arr"]}"#,
+            super::streams_to_command(&["!ticker@arr".to_string()], true)
+        );
+    }
+
+    #[test]
+    fn test_stream_name_and_url() {
+        use super::{stream_name, websocket_url};
+
+        assert_eq!("btcusdt@aggTrade", stream_name('S', "aggTrade", "BTCUSDT"));
+        assert_eq!("btcusd_perp@aggTrade", stream_name('I', "aggTrade", "BTCUSD_PERP"));
+        assert_eq!("BTC-220429-50000-C@trade", stream_name('O', "trade", "BTC-220429-50000-C"));
+
+        assert_eq!("wss://stream.binance.com:9443/stream", websocket_url('S'));
+        assert_eq!("wss://fstream.binance.com/stream", websocket_url('L'));
+        assert_eq!("wss://dstream.binance.com/stream", websocket_url('I'));
+        assert_eq!("wss://stream.opsnest.com/stream", websocket_url('O'));
+    }
+
+    #[test]
+    fn test_check_url() {
+        use super::check_url;
+
+        check_url('S', "wss://stream.binance.com:9443/stream");
+        check_url('L', "wss://fstream.binance.com/stream");
+        check_url('I', "wss://dstream.binance.com:443/stream");
+        check_url('O', "wss://stream.opsnest.com/stream");
+        check_url('L', "ws://127.0.0.1:8080");
+    }
+
+    #[test]
+    #[should_panic(expected = "is the endpoint of market type L, not I")]
+    fn test_check_url_mismatch() {
+        super::check_url('I', "wss://fstream.binance.com/stream");
+    }
+
     #[test]
     fn test_two_topics() {
         let translator = super::BinanceCommandTranslator { market_type: 'S' };
@@ -314,4 +543,95 @@ mod tests {
             commands[0]
         );
     }
+
+    #[test]
+    fn test_many_topics() {
+        for (market_type, max_streams) in [('S', 1024), ('L', 200)] {
+            let translator = super::BinanceCommandTranslator { market_type };
+            let topics = (0..1000)
+                .map(|i| ("trade".to_string(), format!("COIN{i}USDT")))
+                .collect::<Vec<(String, String)>>();
+            let commands = translator.translate_to_commands(true, &topics);
+
+            // 1000 streams are coalesced into a few frames instead of 1000 frames
+            assert!(commands.len() <= 10);
+            let mut num_streams = 0;
+            for command in commands.iter() {
+                assert!(command.len() <= super::WS_FRAME_SIZE);
+                let obj = serde_json::from_str::<serde_json::Value>(command).unwrap();
+                let streams = obj["params"].as_array().unwrap().len();
+                assert!(streams <= max_streams);
+                num_streams += streams;
+            }
+            assert_eq!(1000, num_streams);
+        }
+    }
+
+    #[test]
+    fn test_typed_channels() {
+        use super::BinanceCommandTranslator;
+        use crate::Channel;
+
+        assert_eq!("aggTrade", BinanceCommandTranslator::to_raw_channel(&Channel::AggTrade));
+        assert_eq!("trade", BinanceCommandTranslator::to_raw_channel(&Channel::Trade));
+        assert_eq!("depth@100ms", BinanceCommandTranslator::to_raw_channel(&Channel::OrderBook));
+        assert_eq!("depth20", BinanceCommandTranslator::to_raw_channel(&Channel::OrderBookTopK));
+        assert_eq!("bookTicker", BinanceCommandTranslator::to_raw_channel(&Channel::BookTicker));
+        assert_eq!("ticker", BinanceCommandTranslator::to_raw_channel(&Channel::Ticker));
+        assert_eq!("kline_1m", BinanceCommandTranslator::to_raw_channel(&Channel::Kline(60)));
+    }
+
+    #[test]
+    fn test_candlestick_raw_channel() {
+        use super::BinanceCommandTranslator;
+
+        assert_eq!("kline_1M", BinanceCommandTranslator::to_candlestick_raw_channel(2592000));
+        assert_eq!("kline_1m", BinanceCommandTranslator::to_candlestick_raw_channel(60));
+        assert_eq!("kline_1M", BinanceCommandTranslator::to_candlestick_raw_channel_str("1M"));
+        assert_eq!("kline_1w", BinanceCommandTranslator::to_candlestick_raw_channel_str("1w"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_candlestick_raw_channel_invalid() {
+        super::BinanceCommandTranslator::to_candlestick_raw_channel_str("1W");
+    }
+
+    #[test]
+    fn test_l2_raw_channel() {
+        use super::BinanceCommandTranslator;
+
+        assert_eq!("depth", BinanceCommandTranslator::to_l2_raw_channel('S', None, None));
+        assert_eq!(
+            "depth@100ms",
+            BinanceCommandTranslator::to_l2_raw_channel('S', None, Some(100))
+        );
+        assert_eq!("depth", BinanceCommandTranslator::to_l2_raw_channel('S', None, Some(1000)));
+        assert_eq!(
+            "depth20@100ms",
+            BinanceCommandTranslator::to_l2_raw_channel('S', Some(20), Some(100))
+        );
+        assert_eq!("depth5", BinanceCommandTranslator::to_l2_raw_channel('L', Some(5), None));
+        assert_eq!(
+            "depth10@500ms",
+            BinanceCommandTranslator::to_l2_raw_channel('I', Some(10), Some(500))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_l2_raw_channel_invalid_depth() {
+        super::BinanceCommandTranslator::to_l2_raw_channel('S', Some(50), None);
+    }
+
+    #[test]
+    fn test_handle_message() {
+        let mut handler = super::BinanceMessageHandler {};
+        let trade = r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":1676000000000,"s":"BTCUSDT","t":1,"p":"21900.00","q":"0.001","b":1,"a":2,"T":1676000000000,"m":true,"M":true}}"#;
+        assert!(matches!(handler.handle_message(trade), MiscMessage::Normal));
+
+        // subscription responses are not dispatched
+        let ack = r#"{"result":null,"id":9527}"#;
+        assert!(matches!(handler.handle_message(ack), MiscMessage::Other));
+    }
 }
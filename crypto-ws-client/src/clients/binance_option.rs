@@ -2,13 +2,18 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 use tokio_tungstenite::tungstenite::Message;
 
+use super::binance::{
+    max_streams_per_command, stream_name, streams_to_command, websocket_url, WS_FRAME_SIZE,
+};
 use crate::{
     clients::common_traits::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, BINANCE_OPTION_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
+        utils::ensure_frame_size,
         ws_client_internal::WSClientInternal,
     },
     WSClient,
@@ -18,8 +23,6 @@ use serde_json::Value;
 
 pub(crate) const EXCHANGE_NAME: &str = "binance";
 
-pub(super) const WEBSOCKET_URL: &str = "wss://stream.opsnest.com/stream";
-
 /// Binance Option market
 ///
 ///   * WebSocket API doc: <https://binance-docs.github.io/apidocs/voptions/en/>
@@ -32,7 +35,7 @@ pub struct BinanceOptionWSClient {
 impl_new_constructor!(
     BinanceOptionWSClient,
     EXCHANGE_NAME,
-    WEBSOCKET_URL,
+    websocket_url('O'),
     BinanceOptionMessageHandler {},
     BinanceOptionCommandTranslator {}
 );
@@ -54,31 +57,16 @@ struct BinanceOptionCommandTranslator {}
 
 impl BinanceOptionCommandTranslator {
     fn topics_to_command(topics: &[(String, String)], subscribe: bool) -> String {
-        let raw_topics = topics
+        let streams = topics
             .iter()
-            .map(|(topic, symbol)| format!("{symbol}@{topic}"))
+            .map(|(channel, symbol)| stream_name('O', channel, symbol))
             .collect::<Vec<String>>();
-        format!(
-            r#"{{"id":9527,"method":"{}","params":{}}}"#,
-            if subscribe { "SUBSCRIBE" } else { "UNSUBSCRIBE" },
-            serde_json::to_string(&raw_topics).unwrap()
-        )
+        streams_to_command(&streams, subscribe)
     }
 
     // see https://binance-docs.github.io/apidocs/voptions/en/#payload-candle
     fn to_candlestick_raw_channel(interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1m",
-            300 => "5m",
-            900 => "15m",
-            1800 => "30m",
-            3600 => "1h",
-            14400 => "4h",
-            86400 => "1d",
-            604800 => "1w",
-            _ => panic!("Binance Option has intervals 1m,5m,15m,30m,1h4h,1d,1w"),
-        };
-        format!("kline_{interval_str}")
+        format!("kline_{}", to_interval_str(EXCHANGE_NAME, BINANCE_OPTION_INTERVALS, interval))
     }
 }
 
@@ -127,8 +115,13 @@ impl MessageHandler for BinanceOptionMessageHandler {
 
 impl CommandTranslator for BinanceOptionCommandTranslator {
     fn translate_to_commands(&self, subscribe: bool, topics: &[(String, String)]) -> Vec<String> {
-        let command = Self::topics_to_command(topics, subscribe);
-        vec![command]
+        ensure_frame_size(
+            topics,
+            subscribe,
+            Self::topics_to_command,
+            WS_FRAME_SIZE,
+            Some(max_streams_per_command('O')),
+        )
     }
 
     fn translate_to_candlestick_commands(
@@ -183,4 +176,18 @@ mod tests {
             commands[0]
         );
     }
+
+    #[test]
+    fn test_many_topics() {
+        let translator = super::BinanceOptionCommandTranslator {};
+        let topics = (0..1000)
+            .map(|i| ("trade".to_string(), format!("BTC-220429-{}-C", 10000 + i * 100)))
+            .collect::<Vec<(String, String)>>();
+        let commands = translator.translate_to_commands(true, &topics);
+
+        assert!(commands.len() > 1 && commands.len() <= 10);
+        for command in commands.iter() {
+            assert!(command.len() <= super::WS_FRAME_SIZE);
+        }
+    }
 }
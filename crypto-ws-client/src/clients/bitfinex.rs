@@ -10,6 +10,7 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, BITFINEX_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
@@ -61,7 +62,7 @@ impl OrderBook for BitfinexWSClient {
             })
             .collect::<Vec<String>>();
 
-        self.send(&commands).await;
+        self.client.subscribe_raw(&commands).await;
     }
 }
 
@@ -76,7 +77,7 @@ impl Level3OrderBook for BitfinexWSClient {
             })
             .collect::<Vec<String>>();
 
-        self.send(&commands).await;
+        self.client.subscribe_raw(&commands).await;
     }
 }
 
@@ -97,21 +98,7 @@ impl BitfinexCommandTranslator {
         )
     }
     fn to_candlestick_command(symbol: &str, interval: usize, subscribe: bool) -> String {
-        let interval_str = match interval {
-            60 => "1m",
-            300 => "5m",
-            900 => "15m",
-            1800 => "30m",
-            3600 => "1h",
-            10800 => "3h",
-            21600 => "6h",
-            43200 => "12h",
-            86400 => "1D",
-            604800 => "7D",
-            1209600 => "14D",
-            2592000 => "1M",
-            _ => panic!("Bitfinex available intervals 1m,5m,15m,30m,1h,3h,6h,12h,1D,7D,14D,1M"),
-        };
+        let interval_str = to_interval_str(EXCHANGE_NAME, BITFINEX_INTERVALS, interval);
 
         format!(
             r#"{{"event": "{}","channel": "candles","key": "trade:{}:{}"}}"#,
@@ -162,8 +149,10 @@ impl MessageHandler for BitfinexMessageHandler {
                             .as_i64()
                             .unwrap();
                         if status == 0 {
-                            std::thread::sleep(Duration::from_secs(15));
-                            MiscMessage::Reconnect
+                            MiscMessage::Sleep(
+                                Duration::from_secs(15),
+                                Box::new(MiscMessage::Reconnect),
+                            )
                         } else {
                             MiscMessage::Other
                         }
@@ -181,8 +170,10 @@ impl MessageHandler for BitfinexMessageHandler {
                                 // resume after receiving the info
                                 // message 20061 (it should take 120 seconds
                                 // at most).
-                                std::thread::sleep(Duration::from_secs(15));
-                                MiscMessage::Other
+                                MiscMessage::Sleep(
+                                    Duration::from_secs(15),
+                                    Box::new(MiscMessage::Other),
+                                )
                             }
                             20061 => {
                                 // Maintenance ended. You can resume normal activity. It is advised
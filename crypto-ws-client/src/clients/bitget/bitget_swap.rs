@@ -5,7 +5,7 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{command_translator::CommandTranslator, ws_client_internal::WSClientInternal},
-    WSClient,
+    FromWSClientBuilder, WSClient, WSClientBuilder,
 };
 
 use super::{
@@ -26,7 +26,14 @@ pub struct BitgetSwapWSClient {
 
 impl BitgetSwapWSClient {
     pub async fn new(tx: std::sync::mpsc::Sender<String>, url: Option<&str>) -> Self {
-        let real_url = match url {
+        Self::from_builder(WSClientBuilder::with_url(url), tx).await
+    }
+}
+
+#[async_trait]
+impl FromWSClientBuilder for BitgetSwapWSClient {
+    async fn from_builder(builder: WSClientBuilder, tx: std::sync::mpsc::Sender<String>) -> Self {
+        let real_url = match builder.url.as_deref() {
             Some(endpoint) => endpoint,
             None => WEBSOCKET_URL,
         };
@@ -37,6 +44,7 @@ impl BitgetSwapWSClient {
                 BitgetMessageHandler {},
                 Some(UPLINK_LIMIT),
                 tx,
+                &builder,
             )
             .await,
             translator: BitgetCommandTranslator::<'M'> {},
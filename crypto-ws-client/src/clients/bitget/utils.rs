@@ -9,6 +9,7 @@ use log::*;
 use serde_json::Value;
 
 use crate::common::{
+    candlestick::{to_interval_str, BITGET_INTERVALS},
     command_translator::CommandTranslator,
     message_handler::{MessageHandler, MiscMessage},
     utils::ensure_frame_size,
@@ -67,19 +68,8 @@ impl<const MARKET_TYPE: char> BitgetCommandTranslator<MARKET_TYPE> {
 
     // https://bitgetlimited.github.io/apidoc/en/spot/#candlesticks-channel
     // https://bitgetlimited.github.io/apidoc/en/mix/#candlesticks-channel
-    fn to_candlestick_raw_channel(interval: usize) -> &'static str {
-        match interval {
-            60 => "candle1m",
-            300 => "candle5m",
-            900 => "candle15m",
-            1800 => "candle30m",
-            3600 => "candle1H",
-            14400 => "candle4H",
-            43200 => "candle12H",
-            86400 => "candle1D",
-            604800 => "candle1W",
-            _ => panic!("Invalid Bitget candlestick interval {interval}"),
-        }
+    fn to_candlestick_raw_channel(interval: usize) -> String {
+        format!("candle{}", to_interval_str(EXCHANGE_NAME, BITGET_INTERVALS, interval))
     }
 }
 
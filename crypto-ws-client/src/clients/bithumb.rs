@@ -59,7 +59,12 @@ struct BithumbCommandTranslator {}
 
 impl MessageHandler for BithumbMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
         let code = obj.get("code").unwrap().as_str().unwrap();
         let code = code.parse::<i64>().unwrap();
         if code < 10000 {
@@ -7,6 +7,7 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, BITMEX_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
@@ -52,7 +53,15 @@ impl_candlestick!(BitmexWSClient);
 panic_l3_orderbook!(BitmexWSClient);
 panic_ticker!(BitmexWSClient);
 
-impl_ws_client_trait!(BitmexWSClient);
+impl_ws_client_trait!(BitmexWSClient, {
+    async fn subscribe_all_trade(&self) {
+        self.client.subscribe_raw(&[r#"{"op":"subscribe","args":["trade"]}"#.to_string()]).await;
+    }
+
+    async fn subscribe_all_bbo(&self) {
+        self.client.subscribe_raw(&[r#"{"op":"subscribe","args":["quote"]}"#.to_string()]).await;
+    }
+});
 
 struct BitmexMessageHandler {}
 struct BitmexCommandTranslator {}
@@ -72,14 +81,7 @@ impl BitmexCommandTranslator {
 
     // see https://www.okx.com/docs-v5/en/#websocket-api-public-channel-candlesticks-channel
     fn to_candlestick_raw_channel(interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1m",
-            300 => "5m",
-            3600 => "1h",
-            86400 => "1d",
-            _ => panic!("BitMEX has intervals 1m,5m,1h,1d"),
-        };
-        format!("tradeBin{interval_str}")
+        format!("tradeBin{}", to_interval_str(EXCHANGE_NAME, BITMEX_INTERVALS, interval))
     }
 }
 
@@ -103,7 +105,7 @@ impl MessageHandler for BitmexMessageHandler {
                 // Rate limit exceeded
                 429 => {
                     error!("Received {} from {}", msg, EXCHANGE_NAME);
-                    std::thread::sleep(Duration::from_secs(3));
+                    MiscMessage::Sleep(Duration::from_secs(3), Box::new(MiscMessage::Other))
                 }
                 400 => {
                     if error_msg.starts_with("Unknown") {
@@ -113,10 +115,13 @@ impl MessageHandler for BitmexMessageHandler {
                     } else {
                         warn!("Received {} from {}", msg, EXCHANGE_NAME);
                     }
+                    MiscMessage::Other
+                }
+                _ => {
+                    error!("Received {} from {}", msg, EXCHANGE_NAME);
+                    MiscMessage::Other
                 }
-                _ => error!("Received {} from {}", msg, EXCHANGE_NAME),
             }
-            MiscMessage::Other
         } else if obj.contains_key("success") || obj.contains_key("info") {
             info!("Received {} from {}", msg, EXCHANGE_NAME);
             MiscMessage::Other
@@ -168,7 +173,11 @@ impl CommandTranslator for BitmexCommandTranslator {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::command_translator::CommandTranslator;
+    use crate::common::{
+        command_translator::CommandTranslator,
+        message_handler::{MessageHandler, MiscMessage},
+    };
+    use std::time::Duration;
 
     #[test]
     fn test_one_topic() {
@@ -199,4 +208,15 @@ mod tests {
             commands[0]
         );
     }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut handler = super::BitmexMessageHandler {};
+        let msg = r#"{"status":429,"error":"Rate limit exceeded, retry in 1 seconds.","meta":{"retryAfter":1},"request":{"op":"subscribe","args":"trade:XBTUSD"}}"#;
+        assert!(matches!(
+            handler.handle_message(msg),
+            MiscMessage::Sleep(duration, inner)
+                if duration == Duration::from_secs(3) && matches!(*inner, MiscMessage::Other)
+        ));
+    }
 }
@@ -77,8 +77,10 @@ impl MessageHandler for BitstampMessageHandler {
             }
             "bts:request_reconnect" => {
                 warn!("Received {}, which means Bitstamp is under maintenance", msg);
-                std::thread::sleep(std::time::Duration::from_secs(20));
-                MiscMessage::Reconnect
+                MiscMessage::Sleep(
+                    std::time::Duration::from_secs(20),
+                    Box::new(MiscMessage::Reconnect),
+                )
             }
             _ => MiscMessage::Normal,
         }
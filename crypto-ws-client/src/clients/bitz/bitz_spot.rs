@@ -10,6 +10,7 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, BITZ_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
@@ -63,7 +64,12 @@ impl MessageHandler for BitzMessageHandler {
         if msg == "pong" {
             return MiscMessage::Pong;
         }
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
 
         if obj.contains_key("action")
             && obj.get("action").unwrap().as_str().unwrap().starts_with("Pushdata.")
@@ -112,21 +118,7 @@ impl BitzCommandTranslator {
     }
 
     fn to_candlestick_command(symbol: &str, interval: usize, subscribe: bool) -> String {
-        let interval_str = match interval {
-            60 => "1min",
-            300 => "5min",
-            900 => "15min",
-            1800 => "30min",
-            3600 => "60min",
-            14400 => "4hour",
-            86400 => "1day",
-            432000 => "5day",
-            604800 => "1week",
-            2592000 => "1mon",
-            _ => panic!(
-                "Bitz available intervals 1min,5min,15min,30min,60min,4hour,1day,5day,1week,1mon"
-            ),
-        };
+        let interval_str = to_interval_str(EXCHANGE_NAME, BITZ_INTERVALS, interval);
         format!(
             r#"{{"action":"Topic.{}", "data":{{"symbol":"{}", "type":"kline", "resolution":"{}", "_CDID":"100002", "dataType":"1"}}, "msg_id":{}}}"#,
             if subscribe { "sub" } else { "unsub" },
@@ -4,7 +4,11 @@ use crate::{
     clients::common_traits::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
-    common::{command_translator::CommandTranslator, ws_client_internal::WSClientInternal},
+    common::{
+        candlestick::{to_interval_str, BYBIT_INTERVALS},
+        command_translator::CommandTranslator,
+        ws_client_internal::WSClientInternal,
+    },
     WSClient,
 };
 
@@ -54,24 +58,7 @@ impl BybitInverseCommandTranslator {
     // https://bybit-exchange.github.io/docs/inverse_futures/#t-websocketklinev2
     // https://bybit-exchange.github.io/docs/inverse/#t-websocketklinev2
     fn to_candlestick_raw_channel(interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1",
-            180 => "3",
-            300 => "5",
-            900 => "15",
-            1800 => "30",
-            3600 => "60",
-            7200 => "120",
-            14400 => "240",
-            21600 => "360",
-            86400 => "D",
-            604800 => "W",
-            2592000 => "M",
-            _ => panic!(
-                "Bybit InverseFuture has intervals 1min,5min,15min,30min,60min,4hour,1day,1week,1mon"
-            ),
-        };
-        format!("klineV2.{interval_str}")
+        format!("klineV2.{}", to_interval_str(EXCHANGE_NAME, BYBIT_INTERVALS, interval))
     }
 }
 
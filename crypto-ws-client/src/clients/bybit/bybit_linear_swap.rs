@@ -4,7 +4,11 @@ use crate::{
     clients::common_traits::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
-    common::{command_translator::CommandTranslator, ws_client_internal::WSClientInternal},
+    common::{
+        candlestick::{to_interval_str, BYBIT_INTERVALS},
+        command_translator::CommandTranslator,
+        ws_client_internal::WSClientInternal,
+    },
     WSClient,
 };
 
@@ -48,24 +52,7 @@ struct BybitLinearCommandTranslator {}
 impl BybitLinearCommandTranslator {
     // https://bybit-exchange.github.io/docs/linear/#t-websocketkline
     fn to_candlestick_raw_channel(interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1",
-            180 => "3",
-            300 => "5",
-            900 => "15",
-            1800 => "30",
-            3600 => "60",
-            7200 => "120",
-            14400 => "240",
-            21600 => "360",
-            86400 => "D",
-            604800 => "W",
-            2592000 => "M",
-            _ => panic!(
-                "Bybit LinearSwap has intervals 1min,5min,15min,30min,60min,4hour,1day,1week,1mon"
-            ),
-        };
-        format!("candle.{interval_str}")
+        format!("candle.{}", to_interval_str(EXCHANGE_NAME, BYBIT_INTERVALS, interval))
     }
 }
 
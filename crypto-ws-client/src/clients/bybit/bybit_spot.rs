@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+
+use crate::{
+    clients::common_traits::{
+        Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
+    },
+    common::{command_translator::CommandTranslator, ws_client_internal::WSClientInternal},
+    WSClient,
+};
+
+use super::utils::{BybitMessageHandler, BybitV5CommandTranslator, EXCHANGE_NAME};
+
+const WEBSOCKET_URL: &str = "wss://stream.bybit.com/v5/public/spot";
+
+/// Bybit Spot market.
+///
+/// * WebSocket API doc: <https://bybit-exchange.github.io/docs/v5/ws/connect>
+/// * Trading at: <https://www.bybit.com/en-US/trade/spot/BTC/USDT>
+pub struct BybitSpotWSClient {
+    client: WSClientInternal<BybitMessageHandler>,
+    translator: BybitV5CommandTranslator,
+}
+
+impl_new_constructor!(
+    BybitSpotWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    BybitMessageHandler {},
+    BybitV5CommandTranslator {}
+);
+
+impl_trait!(Trade, BybitSpotWSClient, subscribe_trade, "publicTrade");
+impl_trait!(OrderBook, BybitSpotWSClient, subscribe_orderbook, "orderbook.50");
+impl_trait!(Ticker, BybitSpotWSClient, subscribe_ticker, "tickers");
+impl_trait!(BBO, BybitSpotWSClient, subscribe_bbo, "orderbook.1");
+impl_candlestick!(BybitSpotWSClient);
+panic_l3_orderbook!(BybitSpotWSClient);
+panic_l2_topk!(BybitSpotWSClient);
+
+impl_ws_client_trait!(BybitSpotWSClient);
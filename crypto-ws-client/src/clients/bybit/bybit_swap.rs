@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+
+use crate::{
+    clients::common_traits::{
+        Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
+    },
+    common::{command_translator::CommandTranslator, ws_client_internal::WSClientInternal},
+    WSClient,
+};
+
+use super::utils::{BybitMessageHandler, BybitV5CommandTranslator, EXCHANGE_NAME};
+
+const WEBSOCKET_URL: &str = "wss://stream.bybit.com/v5/public/linear";
+
+/// Bybit USDT-margined perpetual swap market.
+///
+/// * WebSocket API doc: <https://bybit-exchange.github.io/docs/v5/ws/connect>
+/// * Trading at: <https://www.bybit.com/en-US/trade/usdt/BTCUSDT>
+pub struct BybitSwapWSClient {
+    client: WSClientInternal<BybitMessageHandler>,
+    translator: BybitV5CommandTranslator,
+}
+
+impl_new_constructor!(
+    BybitSwapWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    BybitMessageHandler {},
+    BybitV5CommandTranslator {}
+);
+
+impl_trait!(Trade, BybitSwapWSClient, subscribe_trade, "publicTrade");
+impl_trait!(OrderBook, BybitSwapWSClient, subscribe_orderbook, "orderbook.50");
+impl_trait!(Ticker, BybitSwapWSClient, subscribe_ticker, "tickers");
+impl_trait!(BBO, BybitSwapWSClient, subscribe_bbo, "orderbook.1");
+impl_candlestick!(BybitSwapWSClient);
+panic_l3_orderbook!(BybitSwapWSClient);
+panic_l2_topk!(BybitSwapWSClient);
+
+impl_ws_client_trait!(BybitSwapWSClient);
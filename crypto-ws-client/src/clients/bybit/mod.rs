@@ -1,6 +1,10 @@
 mod bybit_inverse;
 mod bybit_linear_swap;
+mod bybit_spot;
+mod bybit_swap;
 mod utils;
 
 pub use bybit_inverse::BybitInverseWSClient;
 pub use bybit_linear_swap::BybitLinearSwapWSClient;
+pub use bybit_spot::BybitSpotWSClient;
+pub use bybit_swap::BybitSwapWSClient;
@@ -4,7 +4,11 @@ use log::*;
 use serde_json::Value;
 use tokio_tungstenite::tungstenite::Message;
 
-use crate::common::message_handler::{MessageHandler, MiscMessage};
+use crate::common::{
+    candlestick::{to_interval_str, BYBIT_V5_INTERVALS},
+    command_translator::CommandTranslator,
+    message_handler::{MessageHandler, MiscMessage},
+};
 
 pub(super) const EXCHANGE_NAME: &str = "bybit";
 
@@ -20,15 +24,59 @@ pub(super) fn topics_to_command(topics: &[(String, String)], subscribe: bool) ->
     )
 }
 
+// Bybit v5 spot allows at most 10 args per subscribe request, see
+// https://bybit-exchange.github.io/docs/v5/ws/connect#public-channel---args-limits
+const V5_MAX_ARGS: usize = 10;
+
+// Commands of the unified v5 API, e.g., {"op":"subscribe","args":["publicTrade.BTCUSDT"]}
+pub(super) struct BybitV5CommandTranslator {}
+
+impl BybitV5CommandTranslator {
+    // https://bybit-exchange.github.io/docs/v5/websocket/public/kline
+    fn to_candlestick_raw_channel(interval: usize) -> String {
+        format!("kline.{}", to_interval_str(EXCHANGE_NAME, BYBIT_V5_INTERVALS, interval))
+    }
+}
+
+impl CommandTranslator for BybitV5CommandTranslator {
+    fn translate_to_commands(&self, subscribe: bool, topics: &[(String, String)]) -> Vec<String> {
+        topics.chunks(V5_MAX_ARGS).map(|chunk| topics_to_command(chunk, subscribe)).collect()
+    }
+
+    fn translate_to_candlestick_commands(
+        &self,
+        subscribe: bool,
+        symbol_interval_list: &[(String, usize)],
+    ) -> Vec<String> {
+        let topics = symbol_interval_list
+            .iter()
+            .map(|(symbol, interval)| {
+                let channel = Self::to_candlestick_raw_channel(*interval);
+                (channel, symbol.to_string())
+            })
+            .collect::<Vec<(String, String)>>();
+        self.translate_to_commands(subscribe, &topics)
+    }
+}
+
 pub(super) struct BybitMessageHandler {}
 
 impl MessageHandler for BybitMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
 
         if obj.contains_key("topic") && obj.contains_key("data") {
             MiscMessage::Normal
         } else {
+            // v5 contract markets reply {"op":"pong","args":[...]} without `success`
+            if obj.get("op").and_then(|op| op.as_str()) == Some("pong") {
+                return MiscMessage::Pong;
+            }
             if obj.contains_key("success") {
                 if obj.get("success").unwrap().as_bool().unwrap() {
                     info!("Received {} from {}", msg, EXCHANGE_NAME);
@@ -52,12 +100,18 @@ impl MessageHandler for BybitMessageHandler {
         // See:
         // - https://bybit-exchange.github.io/docs/inverse/#t-heartbeat
         // - https://bybit-exchange.github.io/docs/linear/#t-heartbeat
+        // - https://bybit-exchange.github.io/docs/v5/ws/connect#how-to-send-the-heartbeat-packet
         Some((Message::Text(r#"{"op":"ping"}"#.to_string()), 30))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::common::{
+        command_translator::CommandTranslator,
+        message_handler::{MessageHandler, MiscMessage},
+    };
+
     #[test]
     fn test_one_channel() {
         let command =
@@ -81,4 +135,52 @@ mod tests {
             command
         );
     }
+
+    #[test]
+    fn test_v5_args_limit() {
+        let translator = super::BybitV5CommandTranslator {};
+        let topics = (0..12)
+            .map(|i| ("publicTrade".to_string(), format!("COIN{i}USDT")))
+            .collect::<Vec<(String, String)>>();
+        let commands = translator.translate_to_commands(true, &topics);
+
+        assert_eq!(2, commands.len());
+        assert!(commands[0].ends_with(r#""publicTrade.COIN9USDT"]}"#));
+        assert_eq!(
+            r#"{"op":"subscribe","args":["publicTrade.COIN10USDT","publicTrade.COIN11USDT"]}"#,
+            commands[1]
+        );
+    }
+
+    #[test]
+    fn test_v5_candlestick() {
+        let translator = super::BybitV5CommandTranslator {};
+        let commands = translator.translate_to_candlestick_commands(
+            true,
+            &[("BTCUSDT".to_string(), 60), ("BTCUSDT".to_string(), 43200)],
+        );
+
+        assert_eq!(1, commands.len());
+        assert_eq!(
+            r#"{"op":"subscribe","args":["kline.1.BTCUSDT","kline.720.BTCUSDT"]}"#,
+            commands[0]
+        );
+    }
+
+    #[test]
+    fn test_v5_pong() {
+        let mut handler = super::BybitMessageHandler {};
+        assert!(matches!(
+            handler.handle_message(
+                r#"{"success":true,"ret_msg":"pong","conn_id":"0970e817-426e-429a-a679-ff7f55e0b16a","op":"ping"}"#
+            ),
+            MiscMessage::Pong
+        ));
+        assert!(matches!(
+            handler.handle_message(
+                r#"{"req_id":"","op":"pong","args":["1675418560633"],"conn_id":"cfcb4ocsvfriu23r3er0-1b"}"#
+            ),
+            MiscMessage::Pong
+        ));
+    }
 }
@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    clients::common_traits::{
+        Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
+    },
+    common::{
+        command_translator::CommandTranslator,
+        message_handler::{MessageHandler, MiscMessage},
+        ws_client_internal::WSClientInternal,
+    },
+    WSClient,
+};
+use log::*;
+use serde_json::Value;
+
+pub(super) const EXCHANGE_NAME: &str = "coinbase";
+
+const WEBSOCKET_URL: &str = "wss://advanced-trade-ws.coinbase.com";
+
+/// The WebSocket client for Coinbase Advanced Trade.
+///
+/// Coinbase has only Spot market, market data channels don't require
+/// authentication.
+///
+///   * WebSocket API doc: <https://docs.cloud.coinbase.com/advanced-trade-api/docs/ws-overview>
+///   * Trading at: <https://www.coinbase.com/advanced-trade/spot/BTC-USD>
+pub struct CoinbaseWSClient {
+    client: WSClientInternal<CoinbaseMessageHandler>,
+    translator: CoinbaseCommandTranslator,
+}
+
+impl_new_constructor!(
+    CoinbaseWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    CoinbaseMessageHandler {},
+    CoinbaseCommandTranslator {}
+);
+
+impl_trait!(Trade, CoinbaseWSClient, subscribe_trade, "market_trades");
+impl_trait!(Ticker, CoinbaseWSClient, subscribe_ticker, "ticker");
+#[rustfmt::skip]
+impl_trait!(OrderBook, CoinbaseWSClient, subscribe_orderbook, "level2");
+impl_candlestick!(CoinbaseWSClient);
+
+panic_bbo!(CoinbaseWSClient);
+panic_l2_topk!(CoinbaseWSClient);
+panic_l3_orderbook!(CoinbaseWSClient);
+
+impl_ws_client_trait!(CoinbaseWSClient);
+
+struct CoinbaseMessageHandler {}
+struct CoinbaseCommandTranslator {}
+
+impl MessageHandler for CoinbaseMessageHandler {
+    fn handle_message(&mut self, msg: &str) -> MiscMessage {
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
+
+        if obj.get("type").and_then(|x| x.as_str()) == Some("error") {
+            error!("Received {} from {}", msg, EXCHANGE_NAME);
+            if obj
+                .get("message")
+                .and_then(|x| x.as_str())
+                .map(|x| x.contains("failure to subscribe"))
+                .unwrap_or(false)
+            {
+                panic!("Received {msg} from {EXCHANGE_NAME}");
+            }
+            return MiscMessage::Other;
+        }
+
+        match obj.get("channel").and_then(|x| x.as_str()) {
+            Some("subscriptions") => {
+                info!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Other
+            }
+            Some("heartbeats") => {
+                debug!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Other
+            }
+            Some(_) if obj.contains_key("events") => MiscMessage::Normal,
+            _ => {
+                warn!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Other
+            }
+        }
+    }
+
+    fn get_ping_msg_and_interval(&self) -> Option<(Message, u64)> {
+        None
+    }
+}
+
+impl CommandTranslator for CoinbaseCommandTranslator {
+    // Each command subscribes to one channel with multiple products
+    fn translate_to_commands(&self, subscribe: bool, topics: &[(String, String)]) -> Vec<String> {
+        let mut channel_symbols = BTreeMap::<String, Vec<String>>::new();
+        for (channel, symbol) in topics {
+            channel_symbols.entry(channel.to_string()).or_default().push(symbol.to_string());
+        }
+
+        channel_symbols
+            .iter()
+            .map(|(channel, symbols)| {
+                format!(
+                    r#"{{"type":"{}","channel":"{}","product_ids":{}}}"#,
+                    if subscribe { "subscribe" } else { "unsubscribe" },
+                    channel,
+                    serde_json::to_string(symbols).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    // The candles channel pushes 5-minute candlesticks only
+    fn translate_to_candlestick_commands(
+        &self,
+        subscribe: bool,
+        symbol_interval_list: &[(String, usize)],
+    ) -> Vec<String> {
+        let topics = symbol_interval_list
+            .iter()
+            .map(|(symbol, interval)| {
+                if *interval != 300 {
+                    panic!("Coinbase has 5-minute candlesticks only, got {interval} seconds");
+                }
+                ("candles".to_string(), symbol.to_string())
+            })
+            .collect::<Vec<(String, String)>>();
+        self.translate_to_commands(subscribe, &topics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        command_translator::CommandTranslator,
+        message_handler::{MessageHandler, MiscMessage},
+    };
+
+    #[test]
+    fn test_two_symbols() {
+        let translator = super::CoinbaseCommandTranslator {};
+        let commands = translator.translate_to_commands(
+            true,
+            &[
+                ("market_trades".to_string(), "BTC-USD".to_string()),
+                ("market_trades".to_string(), "ETH-USD".to_string()),
+            ],
+        );
+
+        assert_eq!(1, commands.len());
+        assert_eq!(
+            r#"{"type":"subscribe","channel":"market_trades","product_ids":["BTC-USD","ETH-USD"]}"#,
+            commands[0]
+        );
+    }
+
+    #[test]
+    fn test_two_channels() {
+        let translator = super::CoinbaseCommandTranslator {};
+        let commands = translator.translate_to_commands(
+            true,
+            &[
+                ("market_trades".to_string(), "BTC-USD".to_string()),
+                ("level2".to_string(), "BTC-USD".to_string()),
+            ],
+        );
+
+        assert_eq!(2, commands.len());
+        assert_eq!(
+            r#"{"type":"subscribe","channel":"level2","product_ids":["BTC-USD"]}"#,
+            commands[0]
+        );
+        assert_eq!(
+            r#"{"type":"subscribe","channel":"market_trades","product_ids":["BTC-USD"]}"#,
+            commands[1]
+        );
+    }
+
+    #[test]
+    fn test_candlestick() {
+        let translator = super::CoinbaseCommandTranslator {};
+        let commands =
+            translator.translate_to_candlestick_commands(true, &[("BTC-USD".to_string(), 300)]);
+
+        assert_eq!(1, commands.len());
+        assert_eq!(
+            r#"{"type":"subscribe","channel":"candles","product_ids":["BTC-USD"]}"#,
+            commands[0]
+        );
+    }
+
+    #[test]
+    fn test_handle_message() {
+        let mut handler = super::CoinbaseMessageHandler {};
+        let trade = r#"{"channel":"market_trades","client_id":"","timestamp":"2023-02-09T20:19:35.39625135Z","sequence_num":0,"events":[{"type":"snapshot","trades":[{"trade_id":"000000000","product_id":"BTC-USD","price":"21921.74","size":"0.06317385","side":"SELL","time":"2023-02-09T20:19:35.39625135Z"}]}]}"#;
+        assert!(matches!(handler.handle_message(trade), MiscMessage::Normal));
+
+        let subscriptions = r#"{"channel":"subscriptions","client_id":"","timestamp":"2023-02-09T20:19:35.39625135Z","sequence_num":1,"events":[{"subscriptions":{"market_trades":["BTC-USD"]}}]}"#;
+        assert!(matches!(handler.handle_message(subscriptions), MiscMessage::Other));
+    }
+}
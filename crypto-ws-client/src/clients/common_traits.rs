@@ -70,9 +70,11 @@ macro_rules! impl_candlestick {
         #[async_trait]
         impl Candlestick for $struct_name {
             async fn subscribe_candlestick(&self, symbol_interval_list: &[(String, usize)]) {
+                // translate first, an unsupported interval panics before it is tracked
                 let commands =
                     self.translator.translate_to_candlestick_commands(true, symbol_interval_list);
-                self.client.send(&commands).await;
+                self.client.add_candlesticks(symbol_interval_list);
+                self.client.send_tracked(&commands).await;
             }
         }
     };
@@ -158,13 +160,26 @@ macro_rules! impl_new_constructor {
             /// * `tx` - The sending part of a channel
             /// * `url` - Optional server url, usually you don't need specify it
             pub async fn new(tx: std::sync::mpsc::Sender<String>, url: Option<&str>) -> Self {
-                let real_url = match url {
-                    Some(endpoint) => endpoint,
-                    None => $default_url,
-                };
+                <Self as $crate::FromWSClientBuilder>::from_builder(
+                    $crate::WSClientBuilder::with_url(url),
+                    tx,
+                )
+                .await
+            }
+        }
+
+        #[async_trait]
+        impl $crate::FromWSClientBuilder for $struct_name {
+            async fn from_builder(
+                builder: $crate::WSClientBuilder,
+                tx: std::sync::mpsc::Sender<String>,
+            ) -> Self {
+                let real_url = builder.url.as_deref().unwrap_or($default_url);
                 $struct_name {
-                    client: WSClientInternal::connect($exchange, real_url, $handler, None, tx)
-                        .await,
+                    client: WSClientInternal::connect(
+                        $exchange, real_url, $handler, None, tx, &builder,
+                    )
+                    .await,
                     translator: $translator,
                 }
             }
@@ -173,8 +188,14 @@ macro_rules! impl_new_constructor {
 }
 
 /// Implement the WSClient trait.
+///
+/// Methods with a default implementation in WSClient can be overridden by
+/// passing them in an optional block.
 macro_rules! impl_ws_client_trait {
     ($struct_name:ident) => {
+        impl_ws_client_trait!($struct_name, {});
+    };
+    ($struct_name:ident, { $($extra:tt)* }) => {
         #[async_trait]
         impl WSClient for $struct_name {
             async fn subscribe_trade(&self, symbols: &[String]) {
@@ -207,26 +228,53 @@ macro_rules! impl_ws_client_trait {
             }
 
             async fn subscribe(&self, topics: &[(String, String)]) {
+                self.client.add_subscriptions(topics);
                 let commands = self.translator.translate_to_commands(true, topics);
-                self.client.send(&commands).await;
+                self.client.send_tracked(&commands).await;
             }
 
             async fn unsubscribe(&self, topics: &[(String, String)]) {
+                self.client.remove_subscriptions(topics);
                 let commands = self.translator.translate_to_commands(false, topics);
-                self.client.send(&commands).await;
+                self.client.send_tracked(&commands).await;
+            }
+
+            fn subscriptions(&self) -> Vec<(String, String)> {
+                self.client.subscriptions()
+            }
+
+            fn candlestick_subscriptions(&self) -> Vec<(String, usize)> {
+                self.client.candlesticks()
             }
 
             async fn send(&self, commands: &[String]) {
                 self.client.send(commands).await;
             }
 
-            async fn run(&self) {
-                self.client.run().await;
+            async fn run(
+                &self,
+                duration: Option<std::time::Duration>,
+            ) -> Result<(), $crate::WSError> {
+                self.client.run(duration, &self.translator).await
+            }
+
+            fn connection_info(&self) -> $crate::ConnectionInfo {
+                self.client.connection_info()
+            }
+
+            fn dropped_messages(&self) -> u64 {
+                self.client.dropped_messages()
+            }
+
+            fn metrics(&self) -> $crate::WSClientMetrics {
+                self.client.metrics()
             }
 
             async fn close(&self) {
                 self.client.close().await;
             }
+
+            $($extra)*
         }
     };
 }
@@ -1,5 +1,11 @@
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
 use tokio_tungstenite::tungstenite::Message;
 
 use crate::{
@@ -7,12 +13,13 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, DERIBIT_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         utils::{ensure_frame_size, topic_to_raw_channel},
         ws_client_internal::WSClientInternal,
     },
-    WSClient,
+    FromWSClientBuilder, WSClient, WSClientBuilder,
 };
 
 use log::*;
@@ -26,6 +33,41 @@ const WEBSOCKET_URL: &str = "wss://www.deribit.com/ws/api/v2/";
 /// single frame in websocket connection frame exceeds the limit (32 kB)
 const WS_FRAME_SIZE: usize = 32 * 1024;
 
+// Upper bound of request ids, so that a command measured by
+// `ensure_frame_size()` is never shorter than the one sent
+const PLACEHOLDER_ID: u64 = u64::MAX;
+
+fn json_rpc_request(id: u64, method: &str, params: &str) -> String {
+    format!(r#"{{"jsonrpc": "2.0", "id": {id}, "method": "{method}", "params": {params}}}"#)
+}
+
+fn channels_params(channels: &[String]) -> String {
+    format!(r#"{{"channels": {}}}"#, serde_json::to_string(channels).unwrap())
+}
+
+// JSON-RPC requests in flight of one client, so that a response can be
+// matched to its request, e.g., `{"jsonrpc":"2.0","id":1,"error":...}` is a
+// failed subscription of the channels recorded here
+#[derive(Default)]
+struct PendingRequests {
+    next_id: AtomicU64,
+    requests: Mutex<HashMap<u64, (String, Vec<String>)>>,
+}
+
+impl PendingRequests {
+    fn request(&self, method: &str, params: &str, channels: Vec<String>) -> String {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.requests.lock().unwrap().insert(id, (method.to_string(), channels));
+        json_rpc_request(id, method, params)
+    }
+
+    // Removes the request that a response answers, by its id
+    fn take(&self, obj: &HashMap<String, Value>) -> Option<(String, Vec<String>)> {
+        let id = obj.get("id").and_then(|id| id.as_u64())?;
+        self.requests.lock().unwrap().remove(&id)
+    }
+}
+
 /// The WebSocket client for Deribit.
 ///
 /// Deribit has InverseFuture, InverseSwap and Option markets.
@@ -39,13 +81,37 @@ pub struct DeribitWSClient {
     translator: DeribitCommandTranslator,
 }
 
-impl_new_constructor!(
-    DeribitWSClient,
-    EXCHANGE_NAME,
-    WEBSOCKET_URL,
-    DeribitMessageHandler {},
-    DeribitCommandTranslator {}
-);
+impl DeribitWSClient {
+    /// Creates a websocket client.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The sending part of a channel
+    /// * `url` - Optional server url, usually you don't need specify it
+    pub async fn new(tx: std::sync::mpsc::Sender<String>, url: Option<&str>) -> Self {
+        Self::from_builder(WSClientBuilder::with_url(url), tx).await
+    }
+}
+
+#[async_trait]
+impl FromWSClientBuilder for DeribitWSClient {
+    async fn from_builder(builder: WSClientBuilder, tx: std::sync::mpsc::Sender<String>) -> Self {
+        let real_url = builder.url.as_deref().unwrap_or(WEBSOCKET_URL);
+        let requests = Arc::new(PendingRequests::default());
+        DeribitWSClient {
+            client: WSClientInternal::connect(
+                EXCHANGE_NAME,
+                real_url,
+                DeribitMessageHandler { requests: requests.clone() },
+                None,
+                tx,
+                &builder,
+            )
+            .await,
+            translator: DeribitCommandTranslator { requests },
+        }
+    }
+}
 
 #[rustfmt::skip]
 impl_trait!(Trade, DeribitWSClient, subscribe_trade, "trades.SYMBOL.100ms");
@@ -63,47 +129,51 @@ panic_l3_orderbook!(DeribitWSClient);
 
 impl_ws_client_trait!(DeribitWSClient);
 
-struct DeribitMessageHandler {}
-struct DeribitCommandTranslator {}
+struct DeribitMessageHandler {
+    requests: Arc<PendingRequests>,
+}
+struct DeribitCommandTranslator {
+    requests: Arc<PendingRequests>,
+}
 
 impl DeribitCommandTranslator {
     fn topics_to_command(topics: &[(String, String)], subscribe: bool) -> String {
         let raw_channels = topics.iter().map(topic_to_raw_channel).collect::<Vec<String>>();
-        format!(
-            r#"{{"method": "public/{}", "params": {{"channels": {}}}}}"#,
-            if subscribe { "subscribe" } else { "unsubscribe" },
-            serde_json::to_string(&raw_channels).unwrap()
+        json_rpc_request(
+            PLACEHOLDER_ID,
+            if subscribe { "public/subscribe" } else { "public/unsubscribe" },
+            &channels_params(&raw_channels),
         )
     }
 
     fn to_candlestick_channel(interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1",
-            180 => "3",
-            300 => "5",
-            600 => "10",
-            900 => "15",
-            1800 => "30",
-            3600 => "60",
-            7200 => "120",
-            10800 => "180",
-            21600 => "360",
-            43200 => "720",
-            86400 => "1D",
-            _ => panic!("Unknown interval {interval}"),
-        };
+        let interval_str = to_interval_str(EXCHANGE_NAME, DERIBIT_INTERVALS, interval);
         format!("chart.trades.SYMBOL.{interval_str}")
     }
 }
 
 impl MessageHandler for DeribitMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
 
         if obj.contains_key("error") {
-            panic!("Received {msg} from {EXCHANGE_NAME}");
+            match self.requests.take(&obj) {
+                Some((method, channels)) => panic!(
+                    "Received {msg} from {EXCHANGE_NAME} in response to {method} of channels {}",
+                    channels.join(",")
+                ),
+                None => {
+                    panic!("Received {msg} from {EXCHANGE_NAME} in response to unknown request")
+                }
+            }
         } else if obj.contains_key("result") {
-            info!("Received {} from {}", msg, EXCHANGE_NAME);
+            let method = self.requests.take(&obj).map_or("unknown request".to_string(), |x| x.0);
+            info!("Received {} from {} in response to {}", msg, EXCHANGE_NAME, method);
             MiscMessage::Other
         } else if obj.contains_key("method") && obj.contains_key("params") {
             match obj.get("method").unwrap().as_str().unwrap() {
@@ -119,7 +189,8 @@ impl MessageHandler for DeribitMessageHandler {
                         .as_str()
                         .unwrap();
                     if param_type == "test_request" {
-                        let ws_msg = Message::Text(r#"{"method": "public/test"}"#.to_string());
+                        let ws_msg =
+                            Message::Text(self.requests.request("public/test", "{}", Vec::new()));
                         MiscMessage::WebSocket(ws_msg)
                     } else {
                         info!("Received {} from {}", msg, EXCHANGE_NAME);
@@ -144,11 +215,24 @@ impl MessageHandler for DeribitMessageHandler {
 
 impl CommandTranslator for DeribitCommandTranslator {
     fn translate_to_commands(&self, subscribe: bool, topics: &[(String, String)]) -> Vec<String> {
+        let method = if subscribe { "public/subscribe" } else { "public/unsubscribe" };
+        // Replace placeholder ids with ids of this client
         let mut all_commands: Vec<String> =
-            ensure_frame_size(topics, subscribe, Self::topics_to_command, WS_FRAME_SIZE, None);
+            ensure_frame_size(topics, subscribe, Self::topics_to_command, WS_FRAME_SIZE, None)
+                .into_iter()
+                .map(|command| {
+                    let obj = serde_json::from_str::<Value>(&command).unwrap();
+                    let channels: Vec<String> =
+                        serde_json::from_value(obj["params"]["channels"].clone()).unwrap();
+                    self.requests.request(method, &channels_params(&channels), channels)
+                })
+                .collect();
 
-        all_commands
-            .push(r#"{"method": "public/set_heartbeat", "params": {"interval": 10}}"#.to_string());
+        all_commands.push(self.requests.request(
+            "public/set_heartbeat",
+            r#"{"interval": 10}"#,
+            Vec::new(),
+        ));
 
         all_commands
     }
@@ -168,11 +252,17 @@ impl CommandTranslator for DeribitCommandTranslator {
 
 #[cfg(test)]
 mod tests {
+    use super::{DeribitCommandTranslator, DeribitMessageHandler, PendingRequests};
     use crate::common::command_translator::CommandTranslator;
+    use std::sync::Arc;
+
+    fn new_translator() -> DeribitCommandTranslator {
+        DeribitCommandTranslator { requests: Arc::new(PendingRequests::default()) }
+    }
 
     #[test]
     fn test_one_channel() {
-        let translator = super::DeribitCommandTranslator {};
+        let translator = new_translator();
         let commands = translator.translate_to_commands(
             true,
             &[("trades.SYMBOL.100ms".to_string(), "BTC-26MAR21".to_string())],
@@ -180,18 +270,18 @@ mod tests {
 
         assert_eq!(2, commands.len());
         assert_eq!(
-            r#"{"method": "public/subscribe", "params": {"channels": ["trades.BTC-26MAR21.100ms"]}}"#,
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "public/subscribe", "params": {"channels": ["trades.BTC-26MAR21.100ms"]}}"#,
             commands[0]
         );
         assert_eq!(
-            r#"{"method": "public/set_heartbeat", "params": {"interval": 10}}"#,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "public/set_heartbeat", "params": {"interval": 10}}"#,
             commands[1]
         );
     }
 
     #[test]
     fn test_two_channel() {
-        let translator = super::DeribitCommandTranslator {};
+        let translator = new_translator();
         let commands = translator.translate_to_commands(
             true,
             &[
@@ -202,12 +292,73 @@ mod tests {
 
         assert_eq!(2, commands.len());
         assert_eq!(
-            r#"{"method": "public/subscribe", "params": {"channels": ["trades.BTC-26MAR21.100ms","ticker.BTC-26MAR21.100ms"]}}"#,
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "public/subscribe", "params": {"channels": ["trades.BTC-26MAR21.100ms","ticker.BTC-26MAR21.100ms"]}}"#,
             commands[0]
         );
         assert_eq!(
-            r#"{"method": "public/set_heartbeat", "params": {"interval": 10}}"#,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "public/set_heartbeat", "params": {"interval": 10}}"#,
             commands[1]
         );
     }
+
+    #[test]
+    fn test_handle_message() {
+        use crate::common::message_handler::{MessageHandler, MiscMessage};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let translator = new_translator();
+        let mut handler = DeribitMessageHandler { requests: translator.requests.clone() };
+        translator.translate_to_commands(
+            true,
+            &[("trades.SYMBOL.100ms".to_string(), "BTC-PERPETUAL".to_string())],
+        );
+        let response = r#"{"jsonrpc":"2.0","id":1,"result":["trades.BTC-PERPETUAL.100ms"]}"#;
+        assert!(matches!(handler.handle_message(response), MiscMessage::Other));
+        // answered requests are no longer pending
+        assert_eq!(1, translator.requests.requests.lock().unwrap().len());
+
+        let test_request =
+            r#"{"jsonrpc":"2.0","method":"heartbeat","params":{"type":"test_request"}}"#;
+        match handler.handle_message(test_request) {
+            MiscMessage::WebSocket(Message::Text(txt)) => assert_eq!(
+                r#"{"jsonrpc": "2.0", "id": 3, "method": "public/test", "params": {}}"#,
+                txt
+            ),
+            _ => panic!("test_request should be answered"),
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "in response to public/subscribe of channels trades.BTC-PERPETUAL.100ms"
+    )]
+    fn test_error_response() {
+        use crate::common::message_handler::MessageHandler;
+
+        let translator = new_translator();
+        let mut handler = DeribitMessageHandler { requests: translator.requests.clone() };
+        translator.translate_to_commands(
+            true,
+            &[("ticker.SYMBOL.100ms".to_string(), "BTC-PERPETUAL".to_string())],
+        );
+        translator.translate_to_commands(
+            true,
+            &[("trades.SYMBOL.100ms".to_string(), "BTC-PERPETUAL".to_string())],
+        );
+        handler.handle_message(
+            r#"{"jsonrpc":"2.0","id":3,"error":{"message":"Invalid params","code":-32602}}"#,
+        );
+    }
+
+    #[test]
+    fn test_request_ids_per_client() {
+        let topics = [("trades.SYMBOL.100ms".to_string(), "BTC-PERPETUAL".to_string())];
+        let first = new_translator();
+        let second = new_translator();
+        assert_eq!(
+            first.translate_to_commands(true, &topics),
+            second.translate_to_commands(true, &topics)
+        );
+        assert!(first.translate_to_commands(true, &topics)[0].contains(r#""id": 3,"#));
+    }
 }
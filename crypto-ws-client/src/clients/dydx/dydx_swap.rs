@@ -54,7 +54,12 @@ struct DydxCommandTranslator {}
 
 impl MessageHandler for DydxMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
 
         match obj.get("type").unwrap().as_str().unwrap() {
             "error" => {
@@ -5,6 +5,7 @@ use serde_json::Value;
 use tokio_tungstenite::tungstenite::Message;
 
 use crate::common::{
+    candlestick::{to_interval_str, GATE_INTERVALS},
     command_translator::CommandTranslator,
     message_handler::{MessageHandler, MiscMessage},
 };
@@ -17,7 +18,12 @@ pub(super) struct GateCommandTranslator<const MARKET_TYPE: char> {}
 
 impl<const MARKET_TYPE: char> MessageHandler for GateMessageHandler<MARKET_TYPE> {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
 
         // https://www.gate.io/docs/apiv4/ws/en/#server-response
         // Null if the server accepts the client request; otherwise, the detailed reason
@@ -126,19 +132,7 @@ impl<const MARKET_TYPE: char> GateCommandTranslator<MARKET_TYPE> {
     }
 
     fn to_candlestick_command(symbol: &str, interval: usize, subscribe: bool) -> String {
-        let interval_str = match interval {
-            10 => "10s",
-            60 => "1m",
-            300 => "5m",
-            900 => "15m",
-            1800 => "30m",
-            3600 => "1h",
-            14400 => "4h",
-            28800 => "8h",
-            86400 => "1d",
-            604800 => "7d",
-            _ => panic!("Gate available intervals 10s,1m,5m,15m,30m,1h,4h,8h,1d,7d"),
-        };
+        let interval_str = to_interval_str(EXCHANGE_NAME, GATE_INTERVALS, interval);
         format!(
             r#"{{"channel": "{}.candlesticks", "event": "{}", "payload" : ["{}", "{}"]}}"#,
             if MARKET_TYPE == 'S' { "spot" } else { "futures" },
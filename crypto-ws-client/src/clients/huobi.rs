@@ -7,11 +7,12 @@ use tokio_tungstenite::tungstenite::Message;
 
 use crate::{
     common::{
+        candlestick::{to_interval_str, HUOBI_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
     },
-    WSClient,
+    FromWSClientBuilder, WSClient, WSClientBuilder,
 };
 
 pub(crate) const EXCHANGE_NAME: &str = "huobi";
@@ -70,7 +71,14 @@ pub type HuobiOptionWSClient = HuobiWSClient<'O'>;
 
 impl<const URL: char> HuobiWSClient<URL> {
     pub async fn new(tx: std::sync::mpsc::Sender<String>, url: Option<&str>) -> Self {
-        let real_url = match url {
+        Self::from_builder(WSClientBuilder::with_url(url), tx).await
+    }
+}
+
+#[async_trait]
+impl<const URL: char> FromWSClientBuilder for HuobiWSClient<URL> {
+    async fn from_builder(builder: WSClientBuilder, tx: std::sync::mpsc::Sender<String>) -> Self {
+        let real_url = match builder.url.as_deref() {
             Some(endpoint) => endpoint,
             None => {
                 if URL == 'S' {
@@ -95,6 +103,7 @@ impl<const URL: char> HuobiWSClient<URL> {
                 HuobiMessageHandler {},
                 None,
                 tx,
+                &builder,
             )
             .await,
             translator: HuobiCommandTranslator {},
@@ -124,7 +133,7 @@ impl<const URL: char> WSClient for HuobiWSClient<URL> {
                 .iter()
                 .map(|symbol| format!(r#"{{"sub":"market.{symbol}.depth.size_20.high_freq","data_type":"incremental","id": "crypto-ws-client"}}"#))
                 .collect::<Vec<String>>();
-            self.client.send(&commands).await;
+            self.client.subscribe_raw(&commands).await;
         }
     }
 
@@ -158,27 +167,51 @@ impl<const URL: char> WSClient for HuobiWSClient<URL> {
     }
 
     async fn subscribe_candlestick(&self, symbol_interval_list: &[(String, usize)]) {
+        // translate first, an unsupported interval panics before it is tracked
         let commands =
             self.translator.translate_to_candlestick_commands(true, symbol_interval_list);
-        self.client.send(&commands).await;
+        self.client.add_candlesticks(symbol_interval_list);
+        self.client.send_tracked(&commands).await;
     }
 
     async fn subscribe(&self, topics: &[(String, String)]) {
+        self.client.add_subscriptions(topics);
         let commands = self.translator.translate_to_commands(true, topics);
-        self.client.send(&commands).await;
+        self.client.send_tracked(&commands).await;
     }
 
     async fn unsubscribe(&self, topics: &[(String, String)]) {
+        self.client.remove_subscriptions(topics);
         let commands = self.translator.translate_to_commands(false, topics);
-        self.client.send(&commands).await;
+        self.client.send_tracked(&commands).await;
+    }
+
+    fn subscriptions(&self) -> Vec<(String, String)> {
+        self.client.subscriptions()
+    }
+
+    fn candlestick_subscriptions(&self) -> Vec<(String, usize)> {
+        self.client.candlesticks()
     }
 
     async fn send(&self, commands: &[String]) {
         self.client.send(commands).await;
     }
 
-    async fn run(&self) {
-        self.client.run().await;
+    async fn run(&self, duration: Option<std::time::Duration>) -> Result<(), crate::WSError> {
+        self.client.run(duration, &self.translator).await
+    }
+
+    fn connection_info(&self) -> crate::ConnectionInfo {
+        self.client.connection_info()
+    }
+
+    fn dropped_messages(&self) -> u64 {
+        self.client.dropped_messages()
+    }
+
+    fn metrics(&self) -> crate::WSClientMetrics {
+        self.client.metrics()
     }
 
     async fn close(&self) {
@@ -201,19 +234,7 @@ impl HuobiCommandTranslator {
 
     // see https://huobiapi.github.io/docs/dm/v1/en/#subscribe-kline-data
     fn to_candlestick_raw_channel(interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1min",
-            300 => "5min",
-            900 => "15min",
-            1800 => "30min",
-            3600 => "60min",
-            14400 => "4hour",
-            86400 => "1day",
-            604800 => "1week",
-            2592000 => "1mon",
-            _ => panic!("Huobi has intervals 1min,5min,15min,30min,60min,4hour,1day,1week,1mon"),
-        };
-        format!("kline.{interval_str}")
+        format!("kline.{}", to_interval_str(EXCHANGE_NAME, HUOBI_INTERVALS, interval))
     }
 }
 
@@ -68,7 +68,12 @@ impl KrakenCommandTranslator {
 
 impl MessageHandler for KrakenMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
 
         if obj.contains_key("event") {
             let event = obj.get("event").unwrap().as_str().unwrap();
@@ -8,6 +8,7 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, KRAKEN_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
@@ -103,8 +104,10 @@ impl MessageHandler for KrakenMessageHandler {
                     match status {
                         "maintenance" | "cancel_only" => {
                             warn!("Received {}, which means Kraken is in maintenance mode", msg);
-                            std::thread::sleep(std::time::Duration::from_secs(20));
-                            MiscMessage::Reconnect
+                            MiscMessage::Sleep(
+                                std::time::Duration::from_secs(20),
+                                Box::new(MiscMessage::Reconnect),
+                            )
                         }
                         _ => {
                             info!("Received {} from {}", msg, EXCHANGE_NAME);
@@ -190,24 +193,6 @@ impl CommandTranslator for KrakenCommandTranslator {
         subscribe: bool,
         symbol_interval_list: &[(String, usize)],
     ) -> Vec<String> {
-        let valid_set: Vec<usize> =
-            vec![1, 5, 15, 30, 60, 240, 1440, 10080, 21600].into_iter().map(|x| x * 60).collect();
-        let invalid_intervals = symbol_interval_list
-            .iter()
-            .map(|(_, interval)| *interval)
-            .filter(|x| !valid_set.contains(x))
-            .collect::<Vec<usize>>();
-        if !invalid_intervals.is_empty() {
-            panic!(
-                "Invalid intervals: {}, available intervals: {}",
-                invalid_intervals
-                    .into_iter()
-                    .map(|x| x.to_string())
-                    .collect::<Vec<String>>()
-                    .join(","),
-                valid_set.into_iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",")
-            );
-        }
         let symbols_interval_list = Self::convert_symbol_interval_list(symbol_interval_list);
         let commands: Vec<String> = symbols_interval_list
             .into_iter()
@@ -216,7 +201,7 @@ impl CommandTranslator for KrakenCommandTranslator {
                     r#"{{"event":"{}","pair":{},"subscription":{{"name":"ohlc", "interval":{}}}}}"#,
                     if subscribe { "subscribe" } else { "unsubscribe" },
                     serde_json::to_string(&symbols).unwrap(),
-                    interval / 60
+                    to_interval_str(EXCHANGE_NAME, KRAKEN_INTERVALS, interval)
                 )
             })
             .collect();
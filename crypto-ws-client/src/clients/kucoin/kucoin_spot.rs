@@ -1,10 +1,16 @@
-use super::utils::{fetch_ws_token, KucoinMessageHandler, EXCHANGE_NAME, UPLINK_LIMIT};
+use super::utils::{
+    fetch_ws_token, KucoinMessageHandler, DEFAULT_PING_INTERVAL, EXCHANGE_NAME, UPLINK_LIMIT,
+};
 use crate::{
     clients::common_traits::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
-    common::{command_translator::CommandTranslator, ws_client_internal::WSClientInternal},
-    WSClient,
+    common::{
+        candlestick::{to_interval_str, KUCOIN_SPOT_INTERVALS},
+        command_translator::CommandTranslator,
+        ws_client_internal::WSClientInternal,
+    },
+    FromWSClientBuilder, WSClient, WSClientBuilder,
 };
 use async_trait::async_trait;
 use std::sync::mpsc::Sender;
@@ -26,21 +32,29 @@ impl KuCoinSpotWSClient {
     /// * `tx` - The sending part of a channel
     /// * `url` - Optional server url, usually you don't need specify it
     pub async fn new(tx: Sender<String>, url: Option<&str>) -> Self {
-        let real_url = match url {
-            Some(endpoint) => endpoint.to_string(),
+        Self::from_builder(WSClientBuilder::with_url(url), tx).await
+    }
+}
+
+#[async_trait]
+impl FromWSClientBuilder for KuCoinSpotWSClient {
+    async fn from_builder(builder: WSClientBuilder, tx: Sender<String>) -> Self {
+        let (real_url, ping_interval) = match builder.url.as_deref() {
+            Some(endpoint) => (endpoint.to_string(), DEFAULT_PING_INTERVAL),
             None => {
                 let ws_token = fetch_ws_token().await;
                 let ws_url = format!("{}?token={}", ws_token.endpoint, ws_token.token);
-                ws_url
+                (ws_url, ws_token.ping_interval)
             }
         };
         KuCoinSpotWSClient {
             client: WSClientInternal::connect(
                 EXCHANGE_NAME,
                 &real_url,
-                KucoinMessageHandler {},
+                KucoinMessageHandler { ping_interval },
                 Some(UPLINK_LIMIT),
                 tx,
+                &builder,
             )
             .await,
             translator: KucoinCommandTranslator {},
@@ -67,25 +81,7 @@ struct KucoinCommandTranslator {}
 
 impl KucoinCommandTranslator {
     fn to_candlestick_channel(symbol: &str, interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1min",
-            180 => "3min",
-            300 => "5min",
-            900 => "15min",
-            1800 => "30min",
-            3600 => "1hour",
-            7200 => "2hour",
-            14400 => "4hour",
-            21600 => "6hour",
-            28800 => "8hour",
-            43200 => "12hour",
-            86400 => "1day",
-            604800 => "1week",
-            _ => panic!(
-                "KuCoin available intervals 1min,3min,5min,15min,30min,1hour,2hour,4hour,6hour,8hour,12hour,1day,1week"
-            ),
-        };
-        format!("{symbol}_{interval_str}")
+        format!("{}_{}", symbol, to_interval_str(EXCHANGE_NAME, KUCOIN_SPOT_INTERVALS, interval))
     }
 }
 
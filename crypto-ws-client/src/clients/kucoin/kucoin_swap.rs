@@ -1,10 +1,16 @@
-use super::utils::{fetch_ws_token, KucoinMessageHandler, EXCHANGE_NAME, UPLINK_LIMIT};
+use super::utils::{
+    fetch_ws_token, KucoinMessageHandler, DEFAULT_PING_INTERVAL, EXCHANGE_NAME, UPLINK_LIMIT,
+};
 use crate::{
     clients::common_traits::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
-    common::{command_translator::CommandTranslator, ws_client_internal::WSClientInternal},
-    WSClient,
+    common::{
+        candlestick::{to_interval_str, KUCOIN_SWAP_INTERVALS},
+        command_translator::CommandTranslator,
+        ws_client_internal::WSClientInternal,
+    },
+    FromWSClientBuilder, WSClient, WSClientBuilder,
 };
 use async_trait::async_trait;
 use std::sync::mpsc::Sender;
@@ -26,21 +32,29 @@ impl KuCoinSwapWSClient {
     /// * `tx` - The sending part of a channel
     /// * `url` - Optional server url, usually you don't need specify it
     pub async fn new(tx: Sender<String>, url: Option<&str>) -> Self {
-        let real_url = match url {
-            Some(endpoint) => endpoint.to_string(),
+        Self::from_builder(WSClientBuilder::with_url(url), tx).await
+    }
+}
+
+#[async_trait]
+impl FromWSClientBuilder for KuCoinSwapWSClient {
+    async fn from_builder(builder: WSClientBuilder, tx: Sender<String>) -> Self {
+        let (real_url, ping_interval) = match builder.url.as_deref() {
+            Some(endpoint) => (endpoint.to_string(), DEFAULT_PING_INTERVAL),
             None => {
                 let ws_token = fetch_ws_token().await;
                 let ws_url = format!("{}?token={}", ws_token.endpoint, ws_token.token);
-                ws_url
+                (ws_url, ws_token.ping_interval)
             }
         };
         KuCoinSwapWSClient {
             client: WSClientInternal::connect(
                 EXCHANGE_NAME,
                 &real_url,
-                KucoinMessageHandler {},
+                KucoinMessageHandler { ping_interval },
                 Some(UPLINK_LIMIT),
                 tx,
+                &builder,
             )
             .await,
             translator: KucoinCommandTranslator {},
@@ -69,14 +83,7 @@ struct KucoinCommandTranslator {}
 
 impl KucoinCommandTranslator {
     fn to_candlestick_channel(symbol: &str, interval: usize) -> String {
-        let valid_set: Vec<usize> =
-            vec![60, 300, 900, 1800, 3600, 7200, 14400, 28800, 43200, 86400, 604800];
-        if !valid_set.contains(&interval) {
-            let joined =
-                valid_set.into_iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",");
-            panic!("KuCoin Swap available intervals {joined}");
-        }
-        format!("{}_{}", symbol, interval / 60)
+        format!("{}_{}", symbol, to_interval_str(EXCHANGE_NAME, KUCOIN_SWAP_INTERVALS, interval))
     }
 }
 
@@ -21,9 +21,13 @@ const MAX_TOPICS_PER_COMMAND: usize = 100;
 pub(super) const UPLINK_LIMIT: (NonZeroU32, std::time::Duration) =
     (nonzero!(100u32), std::time::Duration::from_secs(10));
 
+// Used when the server doesn't specify pingInterval, e.g., a custom url
+pub(super) const DEFAULT_PING_INTERVAL: u64 = 60;
+
 pub(super) struct WebsocketToken {
     pub token: String,
     pub endpoint: String,
+    pub ping_interval: u64, // in seconds
 }
 
 async fn http_post(url: &str) -> Result<String> {
@@ -46,7 +50,11 @@ async fn http_post(url: &str) -> Result<String> {
 // See <https://docs.kucoin.com/#apply-connect-token>
 pub(super) async fn fetch_ws_token() -> WebsocketToken {
     let txt = http_post("https://openapi-v2.kucoin.com/api/v1/bullet-public").await.unwrap();
-    let obj = serde_json::from_str::<HashMap<String, Value>>(&txt).unwrap();
+    parse_ws_token(&txt)
+}
+
+fn parse_ws_token(txt: &str) -> WebsocketToken {
+    let obj = serde_json::from_str::<HashMap<String, Value>>(txt).unwrap();
     let code = obj.get("code").unwrap().as_str().unwrap();
     if code != "200000" {
         panic!("Failed to get token, code is {code}");
@@ -56,9 +64,17 @@ pub(super) async fn fetch_ws_token() -> WebsocketToken {
     let servers = data.get("instanceServers").unwrap().as_array().unwrap();
     let server = servers[0].as_object().unwrap();
 
+    // pingInterval is in milliseconds
+    let ping_interval = server
+        .get("pingInterval")
+        .and_then(|x| x.as_u64())
+        .map(|x| (x / 1000).max(1))
+        .unwrap_or(DEFAULT_PING_INTERVAL);
+
     WebsocketToken {
         token: token.to_string(),
         endpoint: server.get("endpoint").unwrap().as_str().unwrap().to_string(),
+        ping_interval,
     }
 }
 
@@ -101,11 +117,18 @@ pub(super) fn topics_to_commands(topics: &[(String, String)], subscribe: bool) -
     commands
 }
 
-pub(super) struct KucoinMessageHandler {}
+pub(super) struct KucoinMessageHandler {
+    pub ping_interval: u64, // in seconds
+}
 
 impl MessageHandler for KucoinMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
         let msg_type = obj.get("type").unwrap().as_str().unwrap();
         match msg_type {
             "pong" => MiscMessage::Pong,
@@ -132,9 +155,13 @@ impl MessageHandler for KucoinMessageHandler {
         // - https://docs.kucoin.com/#ping
         // - https://docs.kucoin.cc/futures/#ping
         //
-        // If the server has not received the ping from the client for 60 seconds , the
+        // The server specifies pingInterval when applying for the connect token, if it
+        // has not received the ping from the client for pingInterval + pingTimeout, the
         // connection will be disconnected.
-        Some((Message::Text(r#"{"type":"ping", "id": "crypto-ws-client"}"#.to_string()), 60))
+        Some((
+            Message::Text(r#"{"type":"ping", "id": "crypto-ws-client"}"#.to_string()),
+            self.ping_interval,
+        ))
     }
 }
 
@@ -146,6 +173,18 @@ mod tests {
         assert!(!ws_token.token.is_empty())
     }
 
+    #[test]
+    fn test_parse_ws_token() {
+        let txt = r#"{"code":"200000","data":{"token":"2neAiuYvAU61ZD","instanceServers":[{"endpoint":"wss://ws-api-spot.kucoin.com/","encrypt":true,"protocol":"websocket","pingInterval":18000,"pingTimeout":10000}]}}"#;
+        let ws_token = super::parse_ws_token(txt);
+        assert_eq!("2neAiuYvAU61ZD", ws_token.token);
+        assert_eq!("wss://ws-api-spot.kucoin.com/", ws_token.endpoint);
+        assert_eq!(18, ws_token.ping_interval);
+
+        let txt = r#"{"code":"200000","data":{"token":"2neAiuYvAU61ZD","instanceServers":[{"endpoint":"wss://ws-api-spot.kucoin.com/"}]}}"#;
+        assert_eq!(super::DEFAULT_PING_INTERVAL, super::parse_ws_token(txt).ping_interval);
+    }
+
     #[test]
     fn test_topics_to_commands() {
         let commands = super::topics_to_commands(
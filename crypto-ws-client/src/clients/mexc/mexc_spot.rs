@@ -8,11 +8,12 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, MEXC_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
     },
-    WSClient,
+    Channel, WSClient,
 };
 use log::*;
 use serde_json::Value;
@@ -48,7 +49,30 @@ panic_bbo!(MexcSpotWSClient);
 panic_ticker!(MexcSpotWSClient);
 panic_l3_orderbook!(MexcSpotWSClient);
 
-impl_ws_client_trait!(MexcSpotWSClient);
+impl_ws_client_trait!(MexcSpotWSClient, {
+    async fn subscribe_all_ticker(&self) {
+        self.client.subscribe_raw(&[r#"{"op":"sub.overview"}"#.to_string()]).await;
+    }
+
+    async fn subscribe_typed(&self, topics: &[(Channel, String)]) {
+        let (topics, symbol_interval_list) = MexcCommandTranslator::split_typed_topics(topics);
+        if !topics.is_empty() {
+            self.subscribe(&topics).await;
+        }
+        if !symbol_interval_list.is_empty() {
+            <Self as WSClient>::subscribe_candlestick(self, &symbol_interval_list).await;
+        }
+    }
+
+    async fn subscribe_orderbook_topk_with_depth(&self, symbols: &[String], k: usize) {
+        MexcCommandTranslator::check_depth(k);
+        let commands = symbols
+            .iter()
+            .map(|symbol| MexcCommandTranslator::limit_depth_command(symbol, k, true))
+            .collect::<Vec<String>>();
+        self.client.subscribe_raw(&commands).await;
+    }
+});
 
 struct MexcMessageHandler {}
 struct MexcCommandTranslator {}
@@ -94,12 +118,7 @@ impl MessageHandler for MexcMessageHandler {
 impl MexcCommandTranslator {
     fn topic_to_command(channel: &str, symbol: &str, subscribe: bool) -> String {
         if channel == "limit.depth" {
-            format!(
-                r#"{{"op":"{}.{}","symbol":"{}","depth": 5}}"#,
-                if subscribe { "sub" } else { "unsub" },
-                channel,
-                symbol
-            )
+            Self::limit_depth_command(symbol, 5, subscribe)
         } else {
             format!(
                 r#"{{"op":"{}.{}","symbol":"{}"}}"#,
@@ -110,23 +129,50 @@ impl MexcCommandTranslator {
         }
     }
 
+    // limit.depth has 5, 10 or 20 levels
+    fn check_depth(depth: usize) {
+        if !matches!(depth, 5 | 10 | 20) {
+            panic!("MEXC Spot has depth levels 5, 10 and 20, got {depth}");
+        }
+    }
+
+    fn limit_depth_command(symbol: &str, depth: usize, subscribe: bool) -> String {
+        format!(
+            r#"{{"op":"{}.limit.depth","symbol":"{}","depth": {}}}"#,
+            if subscribe { "sub" } else { "unsub" },
+            symbol,
+            depth
+        )
+    }
+
+    // Splits typed topics into raw topics and candlesticks, so that both are
+    // tracked like the ones of subscribe() and subscribe_candlestick()
+    #[allow(clippy::type_complexity)]
+    fn split_typed_topics(
+        topics: &[(Channel, String)],
+    ) -> (Vec<(String, String)>, Vec<(String, usize)>) {
+        let mut raw_topics = Vec::new();
+        let mut symbol_interval_list = Vec::new();
+        for (channel, symbol) in topics {
+            match channel {
+                Channel::Kline(interval) => symbol_interval_list.push((symbol.clone(), *interval)),
+                _ => raw_topics.push((Self::to_raw_channel(channel).to_string(), symbol.clone())),
+            }
+        }
+        (raw_topics, symbol_interval_list)
+    }
+
+    fn to_raw_channel(channel: &Channel) -> &'static str {
+        match channel {
+            Channel::Trade => "deal",
+            Channel::OrderBook => "depth",
+            Channel::OrderBookTopK => "limit.depth",
+            _ => panic!("MEXC Spot does NOT have the {channel:?} channel"),
+        }
+    }
+
     fn interval_to_string(interval: usize) -> String {
-        let tmp = match interval {
-            60 => "Min1",
-            300 => "Min5",
-            900 => "Min15",
-            1800 => "Min30",
-            3600 => "Min60",
-            14400 => "Hour4",
-            28800 => "Hour8",
-            86400 => "Day1",
-            604800 => "Week1",
-            2592000 => "Month1",
-            _ => panic!(
-                "MEXC has intervals Min1,Min5,Min15,Min30,Min60,Hour4,Hour8,Day1,Week1,Month1"
-            ),
-        };
-        tmp.to_string()
+        to_interval_str(EXCHANGE_NAME, MEXC_INTERVALS, interval).to_string()
     }
 }
 
@@ -198,4 +244,47 @@ mod tests {
         assert_eq!(1, commands.len());
         assert_eq!(r#"{"op":"sub.kline","symbol":"BTC_USDT","interval":"Min1"}"#, commands[0]);
     }
+
+    #[test]
+    fn test_typed_channels() {
+        use crate::Channel;
+
+        let translator = super::MexcCommandTranslator {};
+        let (topics, symbol_interval_list) = super::MexcCommandTranslator::split_typed_topics(&[
+            (Channel::Trade, "BTC_USDT".to_string()),
+            (Channel::OrderBookTopK, "BTC_USDT".to_string()),
+            (Channel::Kline(60), "BTC_USDT".to_string()),
+        ]);
+        assert_eq!(vec![("BTC_USDT".to_string(), 60)], symbol_interval_list);
+        let mut commands = translator.translate_to_commands(true, &topics);
+        commands.extend(translator.translate_to_candlestick_commands(true, &symbol_interval_list));
+
+        assert_eq!(3, commands.len());
+        assert_eq!(r#"{"op":"sub.deal","symbol":"BTC_USDT"}"#, commands[0]);
+        assert_eq!(r#"{"op":"sub.limit.depth","symbol":"BTC_USDT","depth": 5}"#, commands[1]);
+        assert_eq!(r#"{"op":"sub.kline","symbol":"BTC_USDT","interval":"Min1"}"#, commands[2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_typed_channel_not_supported() {
+        super::MexcCommandTranslator::split_typed_topics(&[(
+            crate::Channel::AggTrade,
+            "BTC_USDT".to_string(),
+        )]);
+    }
+
+    #[test]
+    fn test_topk_depth() {
+        assert_eq!(
+            r#"{"op":"sub.limit.depth","symbol":"BTC_USDT","depth": 10}"#,
+            super::MexcCommandTranslator::limit_depth_command("BTC_USDT", 10, true)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "MEXC Spot has depth levels 5, 10 and 20, got 50")]
+    fn test_topk_invalid_depth() {
+        super::MexcCommandTranslator::check_depth(50);
+    }
 }
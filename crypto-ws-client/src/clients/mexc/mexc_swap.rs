@@ -8,11 +8,12 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, MEXC_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
     },
-    WSClient,
+    Channel, WSClient,
 };
 
 use log::*;
@@ -50,14 +51,46 @@ impl_candlestick!(MexcSwapWSClient);
 panic_bbo!(MexcSwapWSClient);
 panic_l3_orderbook!(MexcSwapWSClient);
 
-impl_ws_client_trait!(MexcSwapWSClient);
+impl_ws_client_trait!(MexcSwapWSClient, {
+    async fn subscribe_typed(&self, topics: &[(Channel, String)]) {
+        let (topics, symbol_interval_list) = MexcCommandTranslator::split_typed_topics(topics);
+        if !topics.is_empty() {
+            self.subscribe(&topics).await;
+        }
+        if !symbol_interval_list.is_empty() {
+            <Self as WSClient>::subscribe_candlestick(self, &symbol_interval_list).await;
+        }
+    }
+
+    async fn subscribe_orderbook_topk_with_depth(&self, symbols: &[String], k: usize) {
+        MexcCommandTranslator::check_depth(k);
+        let commands = symbols
+            .iter()
+            .map(|symbol| MexcCommandTranslator::depth_full_command(symbol, k, true))
+            .collect::<Vec<String>>();
+        self.client.subscribe_raw(&commands).await;
+    }
+
+    async fn subscribe_funding_rate(&self, symbols: &[String]) {
+        let topics = symbols
+            .iter()
+            .map(|symbol| ("funding.rate".to_string(), symbol.to_string()))
+            .collect::<Vec<(String, String)>>();
+        self.subscribe(&topics).await;
+    }
+});
 
 struct MexcMessageHandler {}
 struct MexcCommandTranslator {}
 
 impl MessageHandler for MexcMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
         if obj.contains_key("channel") && obj.contains_key("data") && obj.contains_key("ts") {
             let channel = obj.get("channel").unwrap().as_str().unwrap();
             match channel {
@@ -97,23 +130,51 @@ impl MexcCommandTranslator {
         )
     }
 
+    // depth.full has 5, 10 or 20 levels
+    fn check_depth(depth: usize) {
+        if !matches!(depth, 5 | 10 | 20) {
+            panic!("MEXC Swap has depth levels 5, 10 and 20, got {depth}");
+        }
+    }
+
+    fn depth_full_command(symbol: &str, limit: usize, subscribe: bool) -> String {
+        format!(
+            r#"{{"method":"{}.depth.full","param":{{"symbol":"{}","limit":{}}}}}"#,
+            if subscribe { "sub" } else { "unsub" },
+            symbol,
+            limit
+        )
+    }
+
+    // Splits typed topics into raw topics and candlesticks, so that both are
+    // tracked like the ones of subscribe() and subscribe_candlestick()
+    #[allow(clippy::type_complexity)]
+    fn split_typed_topics(
+        topics: &[(Channel, String)],
+    ) -> (Vec<(String, String)>, Vec<(String, usize)>) {
+        let mut raw_topics = Vec::new();
+        let mut symbol_interval_list = Vec::new();
+        for (channel, symbol) in topics {
+            match channel {
+                Channel::Kline(interval) => symbol_interval_list.push((symbol.clone(), *interval)),
+                _ => raw_topics.push((Self::to_raw_channel(channel).to_string(), symbol.clone())),
+            }
+        }
+        (raw_topics, symbol_interval_list)
+    }
+
+    fn to_raw_channel(channel: &Channel) -> &'static str {
+        match channel {
+            Channel::Trade => "deal",
+            Channel::OrderBook => "depth",
+            Channel::OrderBookTopK => "depth.full",
+            Channel::Ticker => "ticker",
+            _ => panic!("MEXC Swap does NOT have the {channel:?} channel"),
+        }
+    }
+
     fn interval_to_string(interval: usize) -> String {
-        let tmp = match interval {
-            60 => "Min1",
-            300 => "Min5",
-            900 => "Min15",
-            1800 => "Min30",
-            3600 => "Min60",
-            14400 => "Hour4",
-            28800 => "Hour8",
-            86400 => "Day1",
-            604800 => "Week1",
-            2592000 => "Month1",
-            _ => panic!(
-                "MEXC has intervals Min1,Min5,Min15,Min30,Min60,Hour4,Hour8,Day1,Week1,Month1"
-            ),
-        };
-        tmp.to_string()
+        to_interval_str(EXCHANGE_NAME, MEXC_INTERVALS, interval).to_string()
     }
 }
 
@@ -188,4 +249,41 @@ mod tests {
             commands[0]
         );
     }
+
+    #[test]
+    fn test_typed_channels() {
+        use crate::Channel;
+
+        let translator = super::MexcCommandTranslator {};
+        let (topics, symbol_interval_list) = super::MexcCommandTranslator::split_typed_topics(&[
+            (Channel::Ticker, "BTC_USDT".to_string()),
+            (Channel::OrderBookTopK, "BTC_USDT".to_string()),
+            (Channel::Kline(60), "BTC_USDT".to_string()),
+        ]);
+        assert_eq!(vec![("BTC_USDT".to_string(), 60)], symbol_interval_list);
+        let mut commands = translator.translate_to_commands(true, &topics);
+        commands.extend(translator.translate_to_candlestick_commands(true, &symbol_interval_list));
+
+        assert_eq!(3, commands.len());
+        assert_eq!(r#"{"method":"sub.ticker","param":{"symbol":"BTC_USDT"}}"#, commands[0]);
+        assert_eq!(r#"{"method":"sub.depth.full","param":{"symbol":"BTC_USDT"}}"#, commands[1]);
+        assert_eq!(
+            r#"{"method":"sub.kline","param":{"symbol":"BTC_USDT","interval":"Min1"}}"#,
+            commands[2]
+        );
+    }
+
+    #[test]
+    fn test_topk_depth() {
+        assert_eq!(
+            r#"{"method":"sub.depth.full","param":{"symbol":"BTC_USDT","limit":10}}"#,
+            super::MexcCommandTranslator::depth_full_command("BTC_USDT", 10, true)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "MEXC Swap has depth levels 5, 10 and 20, got 50")]
+    fn test_topk_invalid_depth() {
+        super::MexcCommandTranslator::check_depth(50);
+    }
 }
@@ -10,6 +10,7 @@ pub(super) mod bitmex;
 pub(super) mod bitstamp;
 pub(super) mod bitz;
 pub(super) mod bybit;
+pub(super) mod coinbase;
 pub(super) mod coinbase_pro;
 pub(super) mod deribit;
 pub(super) mod dydx;
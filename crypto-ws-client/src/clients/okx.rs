@@ -14,12 +14,13 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, OKX_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         utils::ensure_frame_size,
         ws_client_internal::WSClientInternal,
     },
-    WSClient,
+    BboCadence, FromWSClientBuilder, WSClient, WSClientBuilder,
 };
 
 pub(crate) const EXCHANGE_NAME: &str = "okx";
@@ -52,7 +53,14 @@ pub struct OkxWSClient {
 
 impl OkxWSClient {
     pub async fn new(tx: std::sync::mpsc::Sender<String>, url: Option<&str>) -> Self {
-        let real_url = match url {
+        Self::from_builder(WSClientBuilder::with_url(url), tx).await
+    }
+}
+
+#[async_trait]
+impl FromWSClientBuilder for OkxWSClient {
+    async fn from_builder(builder: WSClientBuilder, tx: std::sync::mpsc::Sender<String>) -> Self {
+        let real_url = match builder.url.as_deref() {
             Some(endpoint) => endpoint,
             None => WEBSOCKET_URL,
         };
@@ -63,6 +71,7 @@ impl OkxWSClient {
                 OkxMessageHandler {},
                 Some(UPLINK_LIMIT),
                 tx,
+                &builder,
             )
             .await,
             translator: OkxCommandTranslator {},
@@ -81,7 +90,16 @@ impl_trait!(OrderBookTopK, OkxWSClient, subscribe_orderbook_topk, "books5");
 impl_candlestick!(OkxWSClient);
 panic_l3_orderbook!(OkxWSClient);
 
-impl_ws_client_trait!(OkxWSClient);
+impl_ws_client_trait!(OkxWSClient, {
+    async fn subscribe_bbo_with_cadence(&self, symbols: &[String], cadence: BboCadence) {
+        let channel = OkxCommandTranslator::to_bbo_raw_channel(cadence);
+        let topics = symbols
+            .iter()
+            .map(|symbol| (channel.to_string(), symbol.to_string()))
+            .collect::<Vec<(String, String)>>();
+        self.subscribe(&topics).await;
+    }
+});
 
 struct OkxMessageHandler {}
 struct OkxCommandTranslator {}
@@ -105,28 +123,19 @@ impl OkxCommandTranslator {
         )
     }
 
-    // see https://www.okx.com/docs-v5/en/#websocket-api-public-channel-candlesticks-channel
-    fn to_candlestick_raw_channel(interval: usize) -> &'static str {
-        match interval {
-            60 => "candle1m",
-            180 => "candle3m",
-            300 => "candle5m",
-            900 => "candle15m",
-            1800 => "candle30m",
-            3600 => "candle1H",
-            7200 => "candle2H",
-            14400 => "candle4H",
-            21600 => "candle6H",
-            43200 => "candle12H",
-            86400 => "candle1D",
-            172800 => "candle2D",
-            259200 => "candle3D",
-            432000 => "candle5D",
-            604800 => "candle1W",
-            2592000 => "candle1M",
-            _ => panic!("Invalid OKX candlestick interval {interval}"),
+    // bbo-tbt pushes the best bid and offer on every change, tickers pushes
+    // them along with 24hr statistics every 100ms
+    fn to_bbo_raw_channel(cadence: BboCadence) -> &'static str {
+        match cadence {
+            BboCadence::TickByTick => "bbo-tbt",
+            BboCadence::Throttled => "tickers",
         }
     }
+
+    // see https://www.okx.com/docs-v5/en/#websocket-api-public-channel-candlesticks-channel
+    fn to_candlestick_raw_channel(interval: usize) -> String {
+        format!("candle{}", to_interval_str(EXCHANGE_NAME, OKX_INTERVALS, interval))
+    }
 }
 
 impl MessageHandler for OkxMessageHandler {
@@ -199,6 +208,15 @@ impl CommandTranslator for OkxCommandTranslator {
 mod tests {
     use crate::common::command_translator::CommandTranslator;
 
+    #[test]
+    fn test_bbo_cadence() {
+        use super::OkxCommandTranslator;
+        use crate::BboCadence;
+
+        assert_eq!("bbo-tbt", OkxCommandTranslator::to_bbo_raw_channel(BboCadence::TickByTick));
+        assert_eq!("tickers", OkxCommandTranslator::to_bbo_raw_channel(BboCadence::Throttled));
+    }
+
     #[test]
     fn test_one_topic() {
         let translator = super::OkxCommandTranslator {};
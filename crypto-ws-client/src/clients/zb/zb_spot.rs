@@ -10,6 +10,7 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, ZB_SPOT_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
@@ -58,7 +59,12 @@ struct ZbCommandTranslator {}
 
 impl MessageHandler for ZbMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
         let channel = obj["channel"].as_str().unwrap();
 
         if channel == "pong" {
@@ -85,24 +91,7 @@ impl MessageHandler for ZbMessageHandler {
 
 impl ZbCommandTranslator {
     fn to_candlestick_raw_channel(&self, symbol: &str, interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1min",
-            180 => "3min",
-            300 => "5min",
-            900 => "15min",
-            1800 => "30min",
-            3600 => "1hour",
-            7200 => "2hour",
-            14400 => "4hour",
-            21600 => "6hour",
-            43200 => "12hour",
-            86400 => "1day",
-            259200 => "3day",
-            604800 => "1week",
-            _ => panic!(
-                "ZB spot available intervals: 1week, 3day, 1day, 12hour, 6hour, 4hour, 2hour, 1hour, 30min, 15min, 5min, 3min, 1min"
-            ),
-        };
+        let interval_str = to_interval_str(EXCHANGE_NAME, ZB_SPOT_INTERVALS, interval);
         format!("{}_kline_{}", symbol.replace('_', ""), interval_str,)
     }
 }
@@ -10,11 +10,12 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, ZB_SWAP_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
     },
-    WSClient,
+    FromWSClientBuilder, WSClient, WSClientBuilder,
 };
 use log::*;
 
@@ -40,7 +41,14 @@ pub struct ZbSwapWSClient {
 
 impl ZbSwapWSClient {
     pub async fn new(tx: std::sync::mpsc::Sender<String>, url: Option<&str>) -> Self {
-        let real_url = match url {
+        Self::from_builder(WSClientBuilder::with_url(url), tx).await
+    }
+}
+
+#[async_trait]
+impl FromWSClientBuilder for ZbSwapWSClient {
+    async fn from_builder(builder: WSClientBuilder, tx: std::sync::mpsc::Sender<String>) -> Self {
+        let real_url = match builder.url.as_deref() {
             Some(endpoint) => endpoint,
             None => WEBSOCKET_URL,
         };
@@ -51,6 +59,7 @@ impl ZbSwapWSClient {
                 ZbMessageHandler {},
                 Some(UPLINK_LIMIT),
                 tx,
+                &builder,
             )
             .await,
             translator: ZbCommandTranslator {},
@@ -85,7 +94,12 @@ impl MessageHandler for ZbMessageHandler {
             error!("Received {} from {}", msg, EXCHANGE_NAME);
             return MiscMessage::Other;
         }
-        let obj = serde_json::from_str::<HashMap<String, Value>>(msg).unwrap();
+        let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+        if resp.is_err() {
+            error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+            return MiscMessage::Other;
+        }
+        let obj = resp.unwrap();
         if obj.contains_key("channel") && obj.contains_key("data") {
             MiscMessage::Normal
         } else {
@@ -102,17 +116,7 @@ impl MessageHandler for ZbMessageHandler {
 
 impl ZbCommandTranslator {
     fn to_candlestick_raw_channel(&self, symbol: &str, interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1M",
-            300 => "5M",
-            900 => "15M",
-            1800 => "30M",
-            3600 => "1H",
-            21600 => "6H",
-            86400 => "1D",
-            432000 => "5D",
-            _ => panic!("ZB swap available intervals: 1M,5M,15M, 30M, 1H, 6H, 1D, 5D"),
-        };
+        let interval_str = to_interval_str(EXCHANGE_NAME, ZB_SWAP_INTERVALS, interval);
         format!("{symbol}.KLine_{interval_str}",)
     }
 }
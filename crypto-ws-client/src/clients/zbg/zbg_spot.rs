@@ -7,6 +7,7 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, ZBG_SPOT_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
@@ -83,17 +84,7 @@ impl ZbgCommandTranslator {
     }
 
     fn to_candlestick_raw_channel(&self, symbol: &str, interval: usize) -> String {
-        let interval_str = match interval {
-            60 => "1M",
-            300 => "5M",
-            900 => "15M",
-            1800 => "30M",
-            3600 => "1H",
-            14400 => "4H",
-            86400 => "1D",
-            604800 => "1W",
-            _ => panic!("ZBG spot available intervals 1M,5M,15M,30M,1H,4H,1D,1W"),
-        };
+        let interval_str = to_interval_str(EXCHANGE_NAME, ZBG_SPOT_INTERVALS, interval);
 
         let symbol_id = self
             .symbol_id_map
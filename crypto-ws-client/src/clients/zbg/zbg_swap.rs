@@ -7,6 +7,7 @@ use crate::{
         Candlestick, Level3OrderBook, OrderBook, OrderBookTopK, Ticker, Trade, BBO,
     },
     common::{
+        candlestick::{to_interval_str, ZBG_SWAP_INTERVALS},
         command_translator::CommandTranslator,
         message_handler::{MessageHandler, MiscMessage},
         ws_client_internal::WSClientInternal,
@@ -90,20 +91,14 @@ impl ZbgCommandTranslator {
     }
 
     fn to_candlestick_raw_channel(&self, pair: &str, interval: usize) -> String {
-        let valid_set: Vec<usize> =
-            vec![60, 180, 300, 900, 1800, 3600, 7200, 14400, 21600, 43200, 86400, 604800];
-        if !valid_set.contains(&interval) {
-            let joined =
-                valid_set.into_iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",");
-            panic!("ZBG Swap available intervals {joined}");
-        }
+        let interval_str = to_interval_str(EXCHANGE_NAME, ZBG_SWAP_INTERVALS, interval);
 
         let contract_id = self
             .symbol_id_map
             .get(pair)
             .unwrap_or_else(|| panic!("Failed to find contract_id for {pair}"));
 
-        format!("future_kline-{}-{}", contract_id, interval * 1000)
+        format!("future_kline-{contract_id}-{interval_str}")
     }
 }
 
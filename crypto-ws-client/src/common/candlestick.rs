@@ -0,0 +1,499 @@
+// Candlestick intervals in seconds and their native strings of each exchange
+
+// see https://binance-docs.github.io/apidocs/spot/en/#kline-candlestick-streams
+// `1M` is a calendar month
+pub(crate) const BINANCE_INTERVALS: &[(usize, &str)] = &[
+    (60, "1m"),
+    (180, "3m"),
+    (300, "5m"),
+    (900, "15m"),
+    (1800, "30m"),
+    (3600, "1h"),
+    (7200, "2h"),
+    (14400, "4h"),
+    (21600, "6h"),
+    (28800, "8h"),
+    (43200, "12h"),
+    (86400, "1d"),
+    (259200, "3d"),
+    (604800, "1w"),
+    (2592000, "1M"),
+];
+
+// see https://binance-docs.github.io/apidocs/voptions/en/#payload-candle
+pub(crate) const BINANCE_OPTION_INTERVALS: &[(usize, &str)] = &[
+    (60, "1m"),
+    (300, "5m"),
+    (900, "15m"),
+    (1800, "30m"),
+    (3600, "1h"),
+    (14400, "4h"),
+    (86400, "1d"),
+    (604800, "1w"),
+];
+
+// see https://docs.bitfinex.com/reference/ws-public-candles
+pub(crate) const BITFINEX_INTERVALS: &[(usize, &str)] = &[
+    (60, "1m"),
+    (300, "5m"),
+    (900, "15m"),
+    (1800, "30m"),
+    (3600, "1h"),
+    (10800, "3h"),
+    (21600, "6h"),
+    (43200, "12h"),
+    (86400, "1D"),
+    (604800, "7D"),
+    (1209600, "14D"),
+    (2592000, "1M"),
+];
+
+// Channels are prefixed with `candle`, e.g., `candle1H`
+pub(crate) const BITGET_INTERVALS: &[(usize, &str)] = &[
+    (60, "1m"),
+    (300, "5m"),
+    (900, "15m"),
+    (1800, "30m"),
+    (3600, "1H"),
+    (14400, "4H"),
+    (43200, "12H"),
+    (86400, "1D"),
+    (604800, "1W"),
+];
+
+// Channels are prefixed with `tradeBin`, e.g., `tradeBin1h`
+pub(crate) const BITMEX_INTERVALS: &[(usize, &str)] =
+    &[(60, "1m"), (300, "5m"), (3600, "1h"), (86400, "1d")];
+
+pub(crate) const BITZ_INTERVALS: &[(usize, &str)] = &[
+    (60, "1min"),
+    (300, "5min"),
+    (900, "15min"),
+    (1800, "30min"),
+    (3600, "60min"),
+    (14400, "4hour"),
+    (86400, "1day"),
+    (432000, "5day"),
+    (604800, "1week"),
+    (2592000, "1mon"),
+];
+
+// Legacy inverse and linear swap endpoints
+pub(crate) const BYBIT_INTERVALS: &[(usize, &str)] = &[
+    (60, "1"),
+    (180, "3"),
+    (300, "5"),
+    (900, "15"),
+    (1800, "30"),
+    (3600, "60"),
+    (7200, "120"),
+    (14400, "240"),
+    (21600, "360"),
+    (86400, "D"),
+    (604800, "W"),
+    (2592000, "M"),
+];
+
+// see https://bybit-exchange.github.io/docs/v5/websocket/public/kline
+pub(crate) const BYBIT_V5_INTERVALS: &[(usize, &str)] = &[
+    (60, "1"),
+    (180, "3"),
+    (300, "5"),
+    (900, "15"),
+    (1800, "30"),
+    (3600, "60"),
+    (7200, "120"),
+    (14400, "240"),
+    (21600, "360"),
+    (43200, "720"),
+    (86400, "D"),
+    (604800, "W"),
+    (2592000, "M"),
+];
+
+// Channels are `chart.trades.SYMBOL.INTERVAL`
+pub(crate) const DERIBIT_INTERVALS: &[(usize, &str)] = &[
+    (60, "1"),
+    (180, "3"),
+    (300, "5"),
+    (600, "10"),
+    (900, "15"),
+    (1800, "30"),
+    (3600, "60"),
+    (7200, "120"),
+    (10800, "180"),
+    (21600, "360"),
+    (43200, "720"),
+    (86400, "1D"),
+];
+
+// Spot and futures markets have the same intervals
+pub(crate) const GATE_INTERVALS: &[(usize, &str)] = &[
+    (10, "10s"),
+    (60, "1m"),
+    (300, "5m"),
+    (900, "15m"),
+    (1800, "30m"),
+    (3600, "1h"),
+    (14400, "4h"),
+    (28800, "8h"),
+    (86400, "1d"),
+    (604800, "7d"),
+];
+
+pub(crate) const HUOBI_INTERVALS: &[(usize, &str)] = &[
+    (60, "1min"),
+    (300, "5min"),
+    (900, "15min"),
+    (1800, "30min"),
+    (3600, "60min"),
+    (14400, "4hour"),
+    (86400, "1day"),
+    (604800, "1week"),
+    (2592000, "1mon"),
+];
+
+// Intervals in minutes, see https://docs.kraken.com/websockets/#message-subscribe
+pub(crate) const KRAKEN_INTERVALS: &[(usize, &str)] = &[
+    (60, "1"),
+    (300, "5"),
+    (900, "15"),
+    (1800, "30"),
+    (3600, "60"),
+    (14400, "240"),
+    (86400, "1440"),
+    (604800, "10080"),
+    (1296000, "21600"),
+];
+
+pub(crate) const KUCOIN_SPOT_INTERVALS: &[(usize, &str)] = &[
+    (60, "1min"),
+    (180, "3min"),
+    (300, "5min"),
+    (900, "15min"),
+    (1800, "30min"),
+    (3600, "1hour"),
+    (7200, "2hour"),
+    (14400, "4hour"),
+    (21600, "6hour"),
+    (28800, "8hour"),
+    (43200, "12hour"),
+    (86400, "1day"),
+    (604800, "1week"),
+];
+
+// Intervals in minutes
+pub(crate) const KUCOIN_SWAP_INTERVALS: &[(usize, &str)] = &[
+    (60, "1"),
+    (300, "5"),
+    (900, "15"),
+    (1800, "30"),
+    (3600, "60"),
+    (7200, "120"),
+    (14400, "240"),
+    (28800, "480"),
+    (43200, "720"),
+    (86400, "1440"),
+    (604800, "10080"),
+];
+
+// Spot and Swap markets have the same intervals
+pub(crate) const MEXC_INTERVALS: &[(usize, &str)] = &[
+    (60, "Min1"),
+    (300, "Min5"),
+    (900, "Min15"),
+    (1800, "Min30"),
+    (3600, "Min60"),
+    (14400, "Hour4"),
+    (28800, "Hour8"),
+    (86400, "Day1"),
+    (604800, "Week1"),
+    (2592000, "Month1"),
+];
+
+// Channels are prefixed with `candle`, e.g., `candle1H`
+pub(crate) const OKX_INTERVALS: &[(usize, &str)] = &[
+    (60, "1m"),
+    (180, "3m"),
+    (300, "5m"),
+    (900, "15m"),
+    (1800, "30m"),
+    (3600, "1H"),
+    (7200, "2H"),
+    (14400, "4H"),
+    (21600, "6H"),
+    (43200, "12H"),
+    (86400, "1D"),
+    (172800, "2D"),
+    (259200, "3D"),
+    (432000, "5D"),
+    (604800, "1W"),
+    (2592000, "1M"),
+];
+
+pub(crate) const ZB_SPOT_INTERVALS: &[(usize, &str)] = &[
+    (60, "1min"),
+    (180, "3min"),
+    (300, "5min"),
+    (900, "15min"),
+    (1800, "30min"),
+    (3600, "1hour"),
+    (7200, "2hour"),
+    (14400, "4hour"),
+    (21600, "6hour"),
+    (43200, "12hour"),
+    (86400, "1day"),
+    (259200, "3day"),
+    (604800, "1week"),
+];
+
+pub(crate) const ZB_SWAP_INTERVALS: &[(usize, &str)] = &[
+    (60, "1M"),
+    (300, "5M"),
+    (900, "15M"),
+    (1800, "30M"),
+    (3600, "1H"),
+    (21600, "6H"),
+    (86400, "1D"),
+    (432000, "5D"),
+];
+
+pub(crate) const ZBG_SPOT_INTERVALS: &[(usize, &str)] = &[
+    (60, "1M"),
+    (300, "5M"),
+    (900, "15M"),
+    (1800, "30M"),
+    (3600, "1H"),
+    (14400, "4H"),
+    (86400, "1D"),
+    (604800, "1W"),
+];
+
+// Intervals in milliseconds
+pub(crate) const ZBG_SWAP_INTERVALS: &[(usize, &str)] = &[
+    (60, "60000"),
+    (180, "180000"),
+    (300, "300000"),
+    (900, "900000"),
+    (1800, "1800000"),
+    (3600, "3600000"),
+    (7200, "7200000"),
+    (14400, "14400000"),
+    (21600, "21600000"),
+    (43200, "43200000"),
+    (86400, "86400000"),
+    (604800, "604800000"),
+];
+
+/// Returns the candlestick intervals of an exchange, `None` if the exchange
+/// has no candlestick channel.
+fn candlestick_intervals(
+    exchange: &str,
+    market_type: &str,
+) -> Option<&'static [(usize, &'static str)]> {
+    match (exchange, market_type) {
+        ("binance", "european_option") => Some(BINANCE_OPTION_INTERVALS),
+        ("binance", _) => Some(BINANCE_INTERVALS),
+        ("bitfinex", _) => Some(BITFINEX_INTERVALS),
+        ("bitget", _) => Some(BITGET_INTERVALS),
+        ("bitmex", _) => Some(BITMEX_INTERVALS),
+        ("bitz", "spot") => Some(BITZ_INTERVALS),
+        ("bybit", "inverse_future" | "inverse_swap") => Some(BYBIT_INTERVALS),
+        ("bybit", _) => Some(BYBIT_V5_INTERVALS),
+        ("deribit", _) => Some(DERIBIT_INTERVALS),
+        ("gate", _) => Some(GATE_INTERVALS),
+        ("huobi", _) => Some(HUOBI_INTERVALS),
+        ("kraken", "spot") => Some(KRAKEN_INTERVALS),
+        ("kucoin", "spot") => Some(KUCOIN_SPOT_INTERVALS),
+        ("kucoin", _) => Some(KUCOIN_SWAP_INTERVALS),
+        ("mexc", _) => Some(MEXC_INTERVALS),
+        ("okx", _) => Some(OKX_INTERVALS),
+        ("zb", "spot") => Some(ZB_SPOT_INTERVALS),
+        ("zb", _) => Some(ZB_SWAP_INTERVALS),
+        ("zbg", "spot") => Some(ZBG_SPOT_INTERVALS),
+        ("zbg", _) => Some(ZBG_SWAP_INTERVALS),
+        _ => None,
+    }
+}
+
+/// Converts a candlestick interval in seconds to the native interval string
+/// of an exchange, e.g., 604800 is `1w` in Binance and `Week1` in MEXC.
+///
+/// `market_type` is in snake case, e.g., `spot`, `linear_swap` and
+/// `european_option`, the same as `MarketType` of the crypto-market-type
+/// crate.
+///
+/// Returns `None` if the exchange doesn't have the interval, or has no
+/// candlestick channel.
+pub fn interval_seconds_to_exchange_string(
+    exchange: &str,
+    market_type: &str,
+    secs: usize,
+) -> Option<String> {
+    candlestick_intervals(exchange, market_type)?
+        .iter()
+        .find(|(seconds, _)| *seconds == secs)
+        .map(|(_, interval_str)| interval_str.to_string())
+}
+
+/// Looks up a candlestick interval in the table of a client.
+///
+/// Panics with the available intervals if the exchange doesn't have the
+/// interval, before any command is sent.
+pub(crate) fn to_interval_str(
+    exchange: &str,
+    intervals: &[(usize, &'static str)],
+    secs: usize,
+) -> &'static str {
+    match intervals.iter().find(|(seconds, _)| *seconds == secs) {
+        Some((_, interval_str)) => interval_str,
+        None => panic!(
+            "{exchange} doesn't have the candlestick interval {secs} seconds, available intervals \
+             in seconds: {}",
+            intervals.iter().map(|(seconds, _)| seconds.to_string()).collect::<Vec<_>>().join(",")
+        ),
+    }
+}
+
+/// Native interval strings of a table joined by commas, for error messages.
+pub(crate) fn interval_names(intervals: &[(usize, &str)]) -> String {
+    intervals.iter().map(|(_, interval_str)| *interval_str).collect::<Vec<&str>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKETS: &[(&str, &str)] = &[
+        ("binance", "spot"),
+        ("binance", "linear_swap"),
+        ("binance", "inverse_future"),
+        ("binance", "european_option"),
+        ("bitfinex", "spot"),
+        ("bitfinex", "linear_swap"),
+        ("bitget", "spot"),
+        ("bitget", "linear_swap"),
+        ("bitmex", "inverse_swap"),
+        ("bitz", "spot"),
+        ("bybit", "inverse_future"),
+        ("bybit", "inverse_swap"),
+        ("bybit", "linear_swap"),
+        ("bybit", "spot"),
+        ("deribit", "inverse_swap"),
+        ("gate", "spot"),
+        ("gate", "linear_swap"),
+        ("huobi", "spot"),
+        ("huobi", "inverse_future"),
+        ("kraken", "spot"),
+        ("kucoin", "spot"),
+        ("kucoin", "linear_swap"),
+        ("mexc", "spot"),
+        ("mexc", "linear_swap"),
+        ("okx", "spot"),
+        ("okx", "linear_swap"),
+        ("zb", "spot"),
+        ("zb", "linear_swap"),
+        ("zbg", "spot"),
+        ("zbg", "inverse_swap"),
+    ];
+
+    #[test]
+    fn test_all_tables() {
+        for (exchange, market_type) in MARKETS {
+            let intervals = candlestick_intervals(exchange, market_type).unwrap();
+            assert!(!intervals.is_empty());
+            // ascending, no duplicates
+            assert!(intervals.windows(2).all(|w| w[0].0 < w[1].0), "{exchange} {market_type}");
+            for (secs, interval_str) in intervals {
+                assert_eq!(
+                    Some(interval_str.to_string()),
+                    interval_seconds_to_exchange_string(exchange, market_type, *secs)
+                );
+                assert_eq!(*interval_str, to_interval_str(exchange, intervals, *secs));
+            }
+            assert_eq!(None, interval_seconds_to_exchange_string(exchange, market_type, 1));
+        }
+    }
+
+    #[test]
+    fn test_binance() {
+        for market_type in ["spot", "linear_swap", "inverse_future"] {
+            let to_str = |secs| interval_seconds_to_exchange_string("binance", market_type, secs);
+            assert_eq!(Some("1m"), to_str(60).as_deref());
+            assert_eq!(Some("1h"), to_str(3600).as_deref());
+            assert_eq!(Some("1d"), to_str(86400).as_deref());
+            assert_eq!(Some("1w"), to_str(604800).as_deref());
+            assert_eq!(Some("1M"), to_str(2592000).as_deref());
+            assert_eq!(None, to_str(120));
+        }
+
+        let to_str = |secs| interval_seconds_to_exchange_string("binance", "european_option", secs);
+        assert_eq!(Some("1w"), to_str(604800).as_deref());
+        assert_eq!(None, to_str(2592000));
+    }
+
+    #[test]
+    fn test_mexc() {
+        for market_type in ["spot", "linear_swap", "inverse_swap"] {
+            let to_str = |secs| interval_seconds_to_exchange_string("mexc", market_type, secs);
+            assert_eq!(Some("Min1"), to_str(60).as_deref());
+            assert_eq!(Some("Min60"), to_str(3600).as_deref());
+            assert_eq!(Some("Day1"), to_str(86400).as_deref());
+            assert_eq!(Some("Week1"), to_str(604800).as_deref());
+            assert_eq!(Some("Month1"), to_str(2592000).as_deref());
+            assert_eq!(None, to_str(7200));
+        }
+    }
+
+    #[test]
+    fn test_bybit() {
+        let to_str =
+            |market_type, secs| interval_seconds_to_exchange_string("bybit", market_type, secs);
+        assert_eq!(Some("720"), to_str("linear_swap", 43200).as_deref());
+        assert_eq!(None, to_str("inverse_swap", 43200));
+        assert_eq!(Some("D"), to_str("inverse_future", 86400).as_deref());
+    }
+
+    #[test]
+    fn test_units() {
+        assert_eq!(Some("10s"), interval_seconds_to_exchange_string("gate", "spot", 10).as_deref());
+        assert_eq!(
+            Some("1440"),
+            interval_seconds_to_exchange_string("kraken", "spot", 86400).as_deref()
+        );
+        assert_eq!(
+            Some("480"),
+            interval_seconds_to_exchange_string("kucoin", "linear_swap", 28800).as_deref()
+        );
+        assert_eq!(
+            Some("8hour"),
+            interval_seconds_to_exchange_string("kucoin", "spot", 28800).as_deref()
+        );
+        assert_eq!(
+            Some("60000"),
+            interval_seconds_to_exchange_string("zbg", "inverse_swap", 60).as_deref()
+        );
+        assert_eq!(Some("1M"), interval_seconds_to_exchange_string("zbg", "spot", 60).as_deref());
+    }
+
+    #[test]
+    fn test_unknown_exchange() {
+        assert_eq!(None, interval_seconds_to_exchange_string("unknown", "spot", 60));
+        // no candlestick channel
+        assert_eq!(None, interval_seconds_to_exchange_string("kraken", "inverse_future", 60));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "bitmex doesn't have the candlestick interval 900 seconds, available intervals \
+                    in seconds: 60,300,3600,86400"
+    )]
+    fn test_unsupported_interval() {
+        to_interval_str("bitmex", BITMEX_INTERVALS, 900);
+    }
+
+    #[test]
+    fn test_interval_names() {
+        assert_eq!("1m,5m,1h,1d", interval_names(BITMEX_INTERVALS));
+    }
+}
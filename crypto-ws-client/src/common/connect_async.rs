@@ -1,31 +1,83 @@
 use fast_socks5::client::{Config, Socks5Stream};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{Sink, SinkExt, StreamExt};
 use governor::{Quota, RateLimiter};
 use log::*;
 use nonzero_ext::*;
 use reqwest::Url;
-use std::{env, num::NonZeroU32};
+use std::{env, io::ErrorKind, num::NonZeroU32, time::SystemTime};
 use tokio::{
     io::{AsyncRead, AsyncWrite},
-    sync::mpsc::{Receiver, Sender},
+    sync::mpsc::Sender,
 };
 use tokio_tungstenite::{
     tungstenite::{Error, Message},
     MaybeTlsStream, WebSocketStream,
 };
 
+use super::{
+    message_queue::{message_queue, BackPressure, QueueReceiver},
+    ws_client::ConnectionInfo,
+};
+
+/// Max time to wait for the server to acknowledge a CloseFrame.
+pub(crate) const CLOSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Max number of retries of an outbound message after a transient error.
+const SEND_RETRIES: u32 = 3;
+
+/// Base delay between two retries of an outbound message.
+const SEND_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Default max time of the TCP connection, TLS and websocket handshakes.
+pub(crate) const DEFAULT_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Capacity and policy of the queue between the websocket reader and the
+/// event loop in `run()`, it blocks so that a slow consumer slows down reading
+/// the socket, back-pressure is applied by the queue in front of the consumer.
+const SOCKET_QUEUE: (usize, BackPressure) = (32, BackPressure::Block);
+
 /// Wraps a websocket client inside an event loop, returns a message_rx to
-/// receive messages and a command_tx to send commands to the websocket server.
+/// receive messages, a command_tx to send commands to the websocket server and
+/// parameters of the connection.
 ///
 /// To close the websocket connection, send a `Message::Close` message to the
-/// command_tx.
+/// command_tx, then wait for `command_tx.closed()`, which resolves after the
+/// server acknowledged the CloseFrame or `CLOSE_TIMEOUT` elapsed.
 ///
 /// `limit`, max number of uplink messsages, for example, 100 per 10 seconds
+///
+/// `proxy`, a SOCKS5 proxy url, falls back to the `https_proxy` and
+/// `http_proxy` environment variables if None
+///
+/// `connect_timeout`, max time to establish the connection,
+/// `DEFAULT_CONNECT_TIMEOUT` if None, after which an `Error::Io` of kind
+/// `TimedOut` is returned
 pub async fn connect_async(
     url: &str,
     uplink_limit: Option<(NonZeroU32, std::time::Duration)>,
-) -> Result<(Receiver<Message>, Sender<Message>), Error> {
-    if let Ok(proxy_env) = env::var("https_proxy").or_else(|_| env::var("http_proxy")) {
+    proxy: Option<&str>,
+    connect_timeout: Option<std::time::Duration>,
+) -> Result<(QueueReceiver<Message>, Sender<Message>, ConnectionInfo), Error> {
+    let connect_timeout = connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+    let connecting = connect_async_with_proxy(url, uplink_limit, proxy);
+    match tokio::time::timeout(connect_timeout, connecting).await {
+        Ok(ret) => ret,
+        Err(_) => Err(Error::Io(std::io::Error::new(
+            ErrorKind::TimedOut,
+            format!("connecting to {url} timed out after {connect_timeout:?}"),
+        ))),
+    }
+}
+
+async fn connect_async_with_proxy(
+    url: &str,
+    uplink_limit: Option<(NonZeroU32, std::time::Duration)>,
+    proxy: Option<&str>,
+) -> Result<(QueueReceiver<Message>, Sender<Message>, ConnectionInfo), Error> {
+    let proxy = proxy
+        .map(|proxy| proxy.to_string())
+        .or_else(|| env::var("https_proxy").or_else(|_| env::var("http_proxy")).ok());
+    if let Some(proxy_env) = proxy {
         let proxy_url = Url::parse(&proxy_env).unwrap();
         let proxy_scheme = proxy_url.scheme().to_lowercase();
         if proxy_scheme.as_str() != "socks5" {
@@ -48,31 +100,116 @@ pub async fn connect_async(
         let (ws_stream, _) = tokio_tungstenite::client_async_tls(connect_url, proxy_stream).await?;
         // replaced
         // let ret = tokio_tungstenite::connect_async(url).await;
-        connect_async_internal(ws_stream, uplink_limit).await
+        let connection_info = ConnectionInfo {
+            url: url.to_string(),
+            connected_at: Some(SystemTime::now()),
+            tls_version: tls_version(ws_stream.get_ref()),
+            remote_addr: None,
+        };
+        let (message_rx, command_tx) = connect_async_internal(ws_stream, uplink_limit).await?;
+        Ok((message_rx, command_tx, connection_info))
     } else {
         let (ws_stream, _) = tokio_tungstenite::connect_async(url).await?;
 
-        connect_async_internal(ws_stream, uplink_limit).await
+        let remote_addr = match ws_stream.get_ref() {
+            MaybeTlsStream::Plain(stream) => stream.peer_addr().ok(),
+            #[cfg(feature = "rustls-tls")]
+            MaybeTlsStream::Rustls(stream) => stream.get_ref().0.peer_addr().ok(),
+            #[cfg(feature = "native-tls")]
+            MaybeTlsStream::NativeTls(stream) => {
+                stream.get_ref().get_ref().get_ref().peer_addr().ok()
+            }
+            _ => None,
+        };
+        let connection_info = ConnectionInfo {
+            url: url.to_string(),
+            connected_at: Some(SystemTime::now()),
+            tls_version: tls_version(ws_stream.get_ref()),
+            remote_addr,
+        };
+        let (message_rx, command_tx) = connect_async_internal(ws_stream, uplink_limit).await?;
+        Ok((message_rx, command_tx, connection_info))
+    }
+}
+
+// native-tls doesn't expose the negotiated protocol version
+fn tls_version<S>(stream: &MaybeTlsStream<S>) -> Option<String> {
+    match stream {
+        #[cfg(feature = "rustls-tls")]
+        MaybeTlsStream::Rustls(stream) => {
+            stream.get_ref().1.protocol_version().map(|version| format!("{version:?}"))
+        }
+        _ => None,
+    }
+}
+
+// Spaces uplink messages evenly, one every `duration / max_messages`, so that
+// at most `max_messages` messages are sent in any `duration` window, and a
+// long batch of commands gets paced instead of being rejected by the exchange.
+fn uplink_quota(max_messages: NonZeroU32, duration: std::time::Duration) -> Quota {
+    let period = duration / max_messages.get();
+    Quota::with_period(period).unwrap()
+}
+
+// Errors after which the same connection is still usable
+fn is_transient(err: &Error) -> bool {
+    match err {
+        Error::SendQueueFull(_) => true,
+        Error::Io(err) => matches!(
+            err.kind(),
+            ErrorKind::WouldBlock | ErrorKind::Interrupted | ErrorKind::TimedOut
+        ),
+        _ => false,
+    }
+}
+
+// Errors after which the connection is unusable, other errors only lose the
+// message being sent
+fn is_fatal(err: &Error) -> bool {
+    match err {
+        Error::ConnectionClosed | Error::AlreadyClosed | Error::Protocol(_) | Error::Tls(_) => true,
+        Error::Io(_) => !is_transient(err),
+        _ => false,
+    }
+}
+
+// Retries transient errors up to `SEND_RETRIES` times with a linear backoff
+async fn send_with_retry<W: Sink<Message, Error = Error> + Unpin>(
+    write: &mut W,
+    command: Message,
+) -> Result<(), Error> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match write.send(command.clone()).await {
+            Ok(()) => return Ok(()),
+            Err(err) if attempts <= SEND_RETRIES && is_transient(&err) => {
+                let backoff = SEND_RETRY_BACKOFF * attempts;
+                warn!("Failed to send, error: {}, retrying in {:?}", err, backoff);
+                tokio::time::sleep(backoff).await;
+            }
+            Err(err) => return Err(err),
+        }
     }
 }
 
 async fn connect_async_internal<S: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
     ws_stream: WebSocketStream<MaybeTlsStream<S>>,
     uplink_limit: Option<(NonZeroU32, std::time::Duration)>,
-) -> Result<(Receiver<Message>, Sender<Message>), Error> {
+) -> Result<(QueueReceiver<Message>, Sender<Message>), Error> {
     let (command_tx, mut command_rx) = tokio::sync::mpsc::channel::<Message>(1);
-    let (message_tx, message_rx) = tokio::sync::mpsc::channel::<Message>(32);
+    let (message_tx, message_rx) = message_queue::<Message>(SOCKET_QUEUE.0, SOCKET_QUEUE.1);
 
     let (mut write, mut read) = ws_stream.split();
 
-    let limiter = if let Some((max_burst, duration)) = uplink_limit {
-        let quota = Quota::with_period(duration).unwrap().allow_burst(max_burst);
-        RateLimiter::direct(quota)
+    let limiter = if let Some((max_messages, duration)) = uplink_limit {
+        RateLimiter::direct(uplink_quota(max_messages, duration))
     } else {
         RateLimiter::direct(Quota::per_second(nonzero!(u32::max_value())))
     };
 
     tokio::task::spawn(async move {
+        let mut close_requested = false;
         loop {
             tokio::select! {
               command = command_rx.recv() => {
@@ -89,11 +226,16 @@ async fn connect_async_internal<S: AsyncRead + AsyncWrite + Unpin + Send + 'stat
                             }
                             None => warn!("Received an empty close message"),
                         }
+                        close_requested = true;
                         break; // close the connection and break the loop
                       }
                       _ => {
                         limiter.until_ready().await;
-                        if let Err(err) =write.send(command).await {
+                        if let Err(err) = send_with_retry(&mut write, command).await {
+                          if is_fatal(&err) {
+                            error!("Failed to send, error: {}, closing the connection", err);
+                            break;
+                          }
                           error!("Failed to send, error: {}", err);
                         }
                       }
@@ -121,7 +263,192 @@ async fn connect_async_internal<S: AsyncRead + AsyncWrite + Unpin + Send + 'stat
             };
         }
         _ = write.send(Message::Close(None)).await;
+        if close_requested {
+            // Wait for the server to acknowledge the CloseFrame so that the
+            // connection is not left half-open on the server side
+            let wait_for_ack = async {
+                while let Some(Ok(msg)) = read.next().await {
+                    if let Message::Close(_) = msg {
+                        break;
+                    }
+                }
+            };
+            if tokio::time::timeout(CLOSE_TIMEOUT, wait_for_ack).await.is_err() {
+                warn!("No close acknowledgment within {} seconds", CLOSE_TIMEOUT.as_secs());
+            }
+        }
+        // command_rx is dropped here, which notifies `Sender::closed()`
     });
 
     Ok((message_rx, command_tx))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{connect_async, is_fatal, send_with_retry, uplink_quota, SEND_RETRIES};
+    use futures_util::StreamExt;
+    use governor::{clock::FakeRelativeClock, RateLimiter};
+    use nonzero_ext::nonzero;
+    use std::{
+        io::ErrorKind,
+        pin::Pin,
+        task::{Context, Poll},
+        time::Duration,
+    };
+    use tokio_tungstenite::tungstenite::{Error, Message};
+
+    // A sink which fails with `error` for the first `failures` attempts
+    struct FlakySink {
+        failures: u32,
+        error: fn() -> Error,
+        attempts: u32,
+    }
+
+    impl futures_util::Sink<Message> for FlakySink {
+        type Error = Error;
+
+        fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, _item: Message) -> Result<(), Error> {
+            self.attempts += 1;
+            if self.attempts <= self.failures { Err((self.error)()) } else { Ok(()) }
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn flaky_sink(failures: u32, error: fn() -> Error) -> FlakySink {
+        FlakySink { failures, error, attempts: 0 }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_send_with_retry() {
+        let mut sink = flaky_sink(2, || Error::Io(ErrorKind::Interrupted.into()));
+        assert!(send_with_retry(&mut sink, Message::Text("sub".to_string())).await.is_ok());
+        assert_eq!(3, sink.attempts);
+
+        // gives up after SEND_RETRIES retries
+        let mut sink = flaky_sink(u32::MAX, || Error::Io(ErrorKind::TimedOut.into()));
+        assert!(send_with_retry(&mut sink, Message::Text("sub".to_string())).await.is_err());
+        assert_eq!(SEND_RETRIES + 1, sink.attempts);
+
+        // fatal errors are not retried
+        let mut sink = flaky_sink(u32::MAX, || Error::ConnectionClosed);
+        assert!(send_with_retry(&mut sink, Message::Text("sub".to_string())).await.is_err());
+        assert_eq!(1, sink.attempts);
+    }
+
+    #[test]
+    fn test_is_fatal() {
+        use tokio_tungstenite::tungstenite::error::{CapacityError, ProtocolError};
+
+        assert!(is_fatal(&Error::ConnectionClosed));
+        assert!(is_fatal(&Error::AlreadyClosed));
+        assert!(is_fatal(&Error::Protocol(ProtocolError::SendAfterClosing)));
+        assert!(is_fatal(&Error::Io(ErrorKind::BrokenPipe.into())));
+        // the connection is still usable
+        assert!(!is_fatal(&Error::Io(ErrorKind::TimedOut.into())));
+        assert!(!is_fatal(&Error::SendQueueFull(Message::Text("sub".to_string()))));
+        assert!(!is_fatal(&Error::Capacity(CapacityError::MessageTooLong {
+            size: 2 << 20,
+            max_size: 1 << 20
+        })));
+        assert!(!is_fatal(&Error::Utf8));
+    }
+
+    #[test]
+    fn test_uplink_quota() {
+        let quota = uplink_quota(nonzero!(5u32), Duration::from_secs(1));
+        assert_eq!(1, quota.burst_size().get());
+        assert_eq!(Duration::from_millis(200), quota.replenish_interval());
+
+        // a sender which keeps trying every millisecond for 3 seconds
+        let clock = FakeRelativeClock::default();
+        let limiter = RateLimiter::direct_with_clock(quota, &clock);
+        let mut sent_at = Vec::new();
+        for millis in 0..3000u64 {
+            if limiter.check().is_ok() {
+                sent_at.push(millis);
+            }
+            clock.advance(Duration::from_millis(1));
+        }
+        assert_eq!(15, sent_at.len());
+        // no more than 5 messages in any one-second window
+        for start in 0..2000 {
+            let in_window = sent_at.iter().filter(|t| **t >= start && **t < start + 1000).count();
+            assert!(in_window <= 5, "{in_window} messages in [{start}, {})", start + 1000);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_close_waits_for_ack() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            // tungstenite replies to a CloseFrame automatically
+            let mut received_close = false;
+            while let Some(Ok(msg)) = ws_stream.next().await {
+                if let Message::Close(_) = msg {
+                    received_close = true;
+                }
+            }
+            received_close
+        });
+
+        let (_message_rx, command_tx, _) =
+            connect_async(&format!("ws://{addr}"), None, None, None).await.unwrap();
+        command_tx.send(Message::Close(None)).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), command_tx.closed()).await.unwrap();
+        assert!(server.await.unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_connection_info() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws_stream.next().await {}
+        });
+
+        let url = format!("ws://{addr}");
+        let (_message_rx, command_tx, connection_info) =
+            connect_async(&url, None, None, None).await.unwrap();
+        assert_eq!(url, connection_info.url);
+        assert!(connection_info.connected_at.is_some());
+        assert_eq!(None, connection_info.tls_version);
+        assert_eq!(Some(addr), connection_info.remote_addr);
+        command_tx.send(Message::Close(None)).await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_connect_timeout() {
+        // the server accepts TCP connections but never answers the handshake
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let url = format!("ws://{addr}");
+        let connecting = connect_async(&url, None, None, Some(Duration::from_millis(200)));
+        let ret = tokio::time::timeout(Duration::from_secs(5), connecting)
+            .await
+            .expect("connect_async() should time out by itself");
+        assert!(matches!(ret, Err(Error::Io(err)) if err.kind() == ErrorKind::TimedOut));
+    }
+}
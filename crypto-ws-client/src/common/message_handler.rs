@@ -1,3 +1,4 @@
+use std::time::Duration;
 use tokio_tungstenite::tungstenite::Message;
 
 #[derive(Debug)]
@@ -8,6 +9,9 @@ pub(crate) enum MiscMessage {
     Pong,               // Pong message from the server
     Reconnect,          // Needs to reconnect
     Other,              // Other messages will be ignored
+    // Pauses the event loop then handles the inner message, handlers must not
+    // call std::thread::sleep(), which blocks a current_thread runtime
+    Sleep(Duration, Box<MiscMessage>),
 }
 
 /// Exchange-specific message handler.
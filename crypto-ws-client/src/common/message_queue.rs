@@ -0,0 +1,327 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{RecvError, RecvTimeoutError, TryRecvError},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Notify;
+
+/// What to do with a new message when the internal message queue is full,
+/// which happens when messages are received faster than they are handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackPressure {
+    /// Stop reading from the websocket until there is room, which might get
+    /// the connection dropped by the exchange
+    Block,
+    /// Drop the oldest message in the queue to make room
+    DropOldest,
+    /// Drop the new message
+    DropNewest,
+}
+
+struct MessageQueue<T> {
+    queue: Mutex<VecDeque<T>>,
+    capacity: usize,
+    policy: BackPressure,
+    closed: AtomicBool,
+    dropped: Arc<AtomicU64>,
+    // notifies the receiver that a message is available
+    readable: Notify,
+    // same as `readable`, for receivers blocking a thread
+    readable_sync: Condvar,
+    // notifies a blocked sender that there is room
+    writable: Notify,
+}
+
+impl<T> MessageQueue<T> {
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.readable.notify_one();
+        self.writable.notify_one();
+        // a blocked receiver checks `closed` while holding the lock
+        let _guard = self.queue.lock().unwrap();
+        self.readable_sync.notify_all();
+    }
+
+    fn notify_readable(&self) {
+        self.readable.notify_one();
+        self.readable_sync.notify_one();
+    }
+}
+
+/// The sending half of a bounded message queue, the queue is closed when it
+/// is dropped.
+pub(crate) struct QueueSender<T> {
+    inner: Arc<MessageQueue<T>>,
+}
+
+/// The receiving half of a bounded message queue, the queue is closed when it
+/// is dropped.
+pub(crate) struct QueueReceiver<T> {
+    inner: Arc<MessageQueue<T>>,
+}
+
+/// Creates a bounded queue with at most `capacity` messages.
+pub(crate) fn message_queue<T>(
+    capacity: usize,
+    policy: BackPressure,
+) -> (QueueSender<T>, QueueReceiver<T>) {
+    assert!(capacity > 0, "capacity must be positive");
+    let inner = Arc::new(MessageQueue {
+        queue: Mutex::new(VecDeque::with_capacity(capacity)),
+        capacity,
+        policy,
+        closed: AtomicBool::new(false),
+        dropped: Arc::new(AtomicU64::new(0)),
+        readable: Notify::new(),
+        readable_sync: Condvar::new(),
+        writable: Notify::new(),
+    });
+    (QueueSender { inner: inner.clone() }, QueueReceiver { inner })
+}
+
+impl<T> QueueSender<T> {
+    /// Pushes a message, returns false if the receiver has been dropped.
+    pub async fn send(&self, msg: T) -> bool {
+        let mut msg = Some(msg);
+        loop {
+            if self.inner.closed.load(Ordering::Acquire) {
+                return false;
+            }
+            {
+                let mut queue = self.inner.queue.lock().unwrap();
+                if queue.len() < self.inner.capacity {
+                    queue.push_back(msg.take().unwrap());
+                    self.inner.notify_readable();
+                    return true;
+                }
+                match self.inner.policy {
+                    BackPressure::Block => (),
+                    BackPressure::DropOldest => {
+                        queue.pop_front();
+                        queue.push_back(msg.take().unwrap());
+                        self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.inner.notify_readable();
+                        return true;
+                    }
+                    BackPressure::DropNewest => {
+                        self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+                        return true;
+                    }
+                }
+            }
+            self.inner.writable.notified().await;
+        }
+    }
+
+    /// Returns the counter of dropped messages.
+    pub fn dropped(&self) -> Arc<AtomicU64> {
+        self.inner.dropped.clone()
+    }
+}
+
+impl<T> QueueReceiver<T> {
+    /// Pops the oldest message, returns None after the sender has been
+    /// dropped and the queue is empty.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            {
+                let mut queue = self.inner.queue.lock().unwrap();
+                if let Some(msg) = queue.pop_front() {
+                    self.inner.writable.notify_one();
+                    return Some(msg);
+                }
+                if self.inner.closed.load(Ordering::Acquire) {
+                    return None;
+                }
+            }
+            self.inner.readable.notified().await;
+        }
+    }
+
+    /// Same as `recv()` but blocks the current thread, gives up after
+    /// `timeout` if it's not None.
+    fn recv_blocking(&self, timeout: Option<Duration>) -> Result<T, RecvTimeoutError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut queue = self.inner.queue.lock().unwrap();
+        loop {
+            if let Some(msg) = queue.pop_front() {
+                self.inner.writable.notify_one();
+                return Ok(msg);
+            }
+            if self.inner.closed.load(Ordering::Acquire) {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            queue = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                    self.inner.readable_sync.wait_timeout(queue, deadline - now).unwrap().0
+                }
+                None => self.inner.readable_sync.wait(queue).unwrap(),
+            };
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for QueueSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueueSender")
+            .field("capacity", &self.inner.capacity)
+            .field("policy", &self.inner.policy)
+            .finish()
+    }
+}
+
+/// The receiving end of messages of a client created by
+/// `WSClientBuilder::build_with_receiver()`.
+///
+/// Unlike `std::sync::mpsc::Receiver`, it holds a bounded number of messages,
+/// and applies the `BackPressure` policy when the consumer falls behind. It
+/// has the same blocking methods, and iterating over it ends after the client
+/// stops.
+pub struct MessageReceiver {
+    inner: QueueReceiver<String>,
+}
+
+impl MessageReceiver {
+    pub(crate) fn new(inner: QueueReceiver<String>) -> Self {
+        MessageReceiver { inner }
+    }
+
+    /// Blocks until a message is available, fails after the client stopped
+    /// and all messages have been received.
+    pub fn recv(&self) -> Result<String, RecvError> {
+        self.inner.recv_blocking(None).map_err(|_| RecvError)
+    }
+
+    /// Same as `recv()`, but waits at most `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<String, RecvTimeoutError> {
+        self.inner.recv_blocking(Some(timeout))
+    }
+
+    /// Returns a message if one is available without blocking.
+    pub fn try_recv(&self) -> Result<String, TryRecvError> {
+        match self.inner.recv_blocking(Some(Duration::ZERO)) {
+            Ok(msg) => Ok(msg),
+            Err(RecvTimeoutError::Timeout) => Err(TryRecvError::Empty),
+            Err(RecvTimeoutError::Disconnected) => Err(TryRecvError::Disconnected),
+        }
+    }
+}
+
+impl Iterator for MessageReceiver {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.recv().ok()
+    }
+}
+
+impl<T> Drop for QueueSender<T> {
+    fn drop(&mut self) {
+        self.inner.close();
+    }
+}
+
+impl<T> Drop for QueueReceiver<T> {
+    fn drop(&mut self) {
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{message_queue, BackPressure};
+    use std::{sync::atomic::Ordering, time::Duration};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_drop_oldest() {
+        let (tx, mut rx) = message_queue::<u32>(2, BackPressure::DropOldest);
+        let dropped = tx.dropped();
+        let producer = tokio::task::spawn(async move {
+            for i in 0..10 {
+                assert!(tx.send(i).await);
+            }
+        });
+
+        // a deliberately slow consumer
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        producer.await.unwrap();
+
+        assert_eq!(Some(8), rx.recv().await);
+        assert_eq!(Some(9), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+        assert_eq!(8, dropped.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_drop_newest() {
+        let (tx, mut rx) = message_queue::<u32>(2, BackPressure::DropNewest);
+        for i in 0..5 {
+            assert!(tx.send(i).await);
+        }
+        let dropped = tx.dropped();
+        drop(tx);
+
+        assert_eq!(Some(0), rx.recv().await);
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+        assert_eq!(3, dropped.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_block() {
+        let (tx, mut rx) = message_queue::<u32>(1, BackPressure::Block);
+        let dropped = tx.dropped();
+        let producer = tokio::task::spawn(async move {
+            for i in 0..5 {
+                assert!(tx.send(i).await);
+            }
+        });
+
+        for i in 0..5 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            assert_eq!(Some(i), rx.recv().await);
+        }
+        producer.await.unwrap();
+        assert_eq!(None, rx.recv().await);
+        assert_eq!(0, dropped.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_receiver_dropped() {
+        let (tx, rx) = message_queue::<u32>(1, BackPressure::Block);
+        assert!(tx.send(0).await);
+        drop(rx);
+        assert!(!tx.send(1).await);
+    }
+
+    #[test]
+    fn test_message_receiver() {
+        use super::MessageReceiver;
+        use std::sync::mpsc::{RecvTimeoutError, TryRecvError};
+
+        let (tx, rx) = message_queue::<String>(2, BackPressure::DropOldest);
+        let rx = MessageReceiver::new(rx);
+        assert_eq!(Err(TryRecvError::Empty), rx.try_recv());
+        assert_eq!(Err(RecvTimeoutError::Timeout), rx.recv_timeout(Duration::from_millis(10)));
+
+        let producer = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            runtime.block_on(async move {
+                for i in 0..5 {
+                    tx.send(i.to_string()).await;
+                }
+            });
+        });
+        producer.join().unwrap();
+        assert_eq!(vec!["3".to_string(), "4".to_string()], rx.collect::<Vec<String>>());
+    }
+}
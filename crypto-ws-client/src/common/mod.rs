@@ -1,6 +1,9 @@
+pub(crate) mod candlestick;
 pub(crate) mod command_translator;
 pub(crate) mod connect_async;
 pub(crate) mod message_handler;
+pub(crate) mod message_queue;
 pub(super) mod utils;
 pub(crate) mod ws_client;
+pub(crate) mod ws_client_builder;
 pub(super) mod ws_client_internal;
@@ -3,7 +3,7 @@
 pub(crate) fn ensure_frame_size(
     topics: &[(String, String)],
     subscribe: bool,
-    topics_to_command: fn(&[(String, String)], bool) -> String,
+    topics_to_command: impl Fn(&[(String, String)], bool) -> String,
     max_bytes: usize,
     max_topics_per_command: Option<usize>,
 ) -> Vec<String> {
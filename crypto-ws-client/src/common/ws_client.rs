@@ -1,4 +1,85 @@
 use async_trait::async_trait;
+use std::{net::SocketAddr, time::SystemTime};
+
+/// Negotiated parameters of the underlying websocket connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    /// The websocket url
+    pub url: String,
+    /// When the websocket handshake completed
+    pub connected_at: Option<SystemTime>,
+    /// The TLS protocol version, e.g., `TLSv1_3`, None for plain connections
+    pub tls_version: Option<String>,
+    /// The address of the remote peer, None if connected through a proxy
+    pub remote_addr: Option<SocketAddr>,
+}
+
+/// Errors that stop a websocket client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WSError {
+    /// Connecting failed `attempts` times in a row, more than allowed by
+    /// `WSClientBuilder::max_reconnects()`, `error` is the last failure.
+    GaveUp { attempts: u32, error: String },
+}
+
+impl std::fmt::Display for WSError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WSError::GaveUp { attempts, error } => {
+                write!(f, "gave up after {attempts} attempts, error: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WSError {}
+
+/// Cumulative counters of a websocket client.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WSClientMetrics {
+    /// Messages received from the server, including control frames
+    pub messages_received: u64,
+    /// Bytes received from the server, before decompression
+    pub bytes_received: u64,
+    /// Messages dispatched to the `tx` channel
+    pub messages_dispatched: u64,
+    /// Messages dropped because the consumer fell behind
+    pub dropped_messages: u64,
+    /// Connection attempts after the first one, i.e., retries of the first
+    /// connection and attempts to reconnect, successful or not
+    pub reconnects: u64,
+    /// When the last message was received, None if nothing was received yet
+    pub last_message_at: Option<SystemTime>,
+}
+
+/// Exchange-agnostic channels, each exchange maps them to its native
+/// channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// Tick-by-tick trades
+    Trade,
+    /// Trades aggregated by taker order, e.g., Binance `aggTrade`
+    AggTrade,
+    /// Incremental level2 orderbook, same as `subscribe_orderbook()`
+    OrderBook,
+    /// Level2 orderbook snapshots, same as `subscribe_orderbook_topk()`
+    OrderBookTopK,
+    /// Best bid and offer, same as `subscribe_bbo()`
+    BookTicker,
+    /// 24hr rolling window ticker, same as `subscribe_ticker()`
+    Ticker,
+    /// Candlesticks with an interval in seconds
+    Kline(usize),
+}
+
+/// Update cadence of BBO channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BboCadence {
+    /// Pushed on every change of the best bid or offer
+    TickByTick,
+    /// Pushed at a fixed interval chosen by the exchange
+    Throttled,
+}
 
 /// The public interface of every WebSocket client.
 #[async_trait]
@@ -29,6 +110,17 @@ pub trait WSClient {
     ///   data.
     async fn subscribe_bbo(&self, symbols: &[String]);
 
+    /// Subscribes to BBO channels with the given update cadence.
+    ///
+    /// Exchanges that can't honor the requested cadence will panic.
+    ///
+    /// * Binance `bookTicker`, tick-by-tick only
+    /// * OKX `bbo-tbt` for tick-by-tick, `tickers` for throttled, which
+    ///   carries the best bid and offer every 100ms
+    async fn subscribe_bbo_with_cadence(&self, _symbols: &[String], cadence: BboCadence) {
+        panic!("BBO cadence {cadence:?} is NOT supported by this exchange");
+    }
+
     /// Subscribes to incremental level2 orderbook channels.
     ///
     /// An incremental level2 orderbook channel sends a snapshot followed by
@@ -58,18 +150,31 @@ pub trait WSClient {
     ///
     /// This function subscribes to exchange specific channels as the following:
     ///
-    /// * Binance `depth5`, every 1000ms
+    /// * Binance `depth20`, top 20, every 1000ms for Spot, 250ms for futures
     /// * Bitfinex has no snapshot channel
     /// * BitMEX `orderBook10`, top 10, every tick
     /// * Bitstamp `order_book`, top 10, every 100ms
     /// * CoinbasePro has no snapshot channel
     /// * Huobi `depth.step1` and `depth.step7`, top 20, every 1s
     /// * Kraken has no snapshot channel
-    /// * MEXC `depth.full` for Swap, top 20, every 100ms; `get.depth` for Spot,
-    ///   full, every 26s
+    /// * MEXC `depth.full` for Swap, top 20, every 100ms; `limit.depth` for
+    ///   Spot, top 5
     /// * OKEx `depth5`, top 5, every 100ms
     async fn subscribe_orderbook_topk(&self, symbols: &[String]);
 
+    /// Subscribes to level2 orderbook top-k snapshot channels with `k` levels.
+    ///
+    /// `k` must be one of the levels the exchange provides, otherwise this
+    /// function will panic. Exchanges that don't support choosing the depth
+    /// will panic too. `k` is validated before any command is sent, so a panic
+    /// leaves subscriptions of the client unchanged.
+    ///
+    /// * Binance 5, 10 or 20, same as `subscribe_l2(symbols, Some(k), None)`
+    /// * MEXC 5, 10 or 20
+    async fn subscribe_orderbook_topk_with_depth(&self, _symbols: &[String], k: usize) {
+        panic!("Choosing top-{k} orderbook depth is NOT supported by this exchange");
+    }
+
     /// Subscribes to level3 orderebook channels.
     ///
     /// **Only bitfinex, bitstamp, coinbase_pro and kucoin have level3 orderbook
@@ -97,8 +202,116 @@ pub trait WSClient {
     ///
     /// Not all exchanges have candlestick channels, for example, Bitstamp
     /// and CoinbasePro.
+    ///
+    /// `2592000` stands for the calendar month interval of each exchange,
+    /// e.g., Binance `1M`, OKX `candle1M` and MEXC `Month1`, not 30 days.
+    ///
+    /// Panics with the available intervals if the exchange doesn't have an
+    /// interval, before any command is sent, see
+    /// [`interval_seconds_to_exchange_string`](crate::interval_seconds_to_exchange_string)
+    /// to check intervals beforehand.
     async fn subscribe_candlestick(&self, symbol_interval_list: &[(String, usize)]);
 
+    /// Subscribes to candlestick channels with exchange-native intervals.
+    ///
+    /// `symbol_interval_list` is a list of symbols and interval strings, which
+    /// are passed to the exchange as is, calling this function with exchanges
+    /// that don't support it will panic.
+    ///
+    /// * Binance `1m`, `3m`, `5m`, `15m`, `30m`, `1h`, `2h`, `4h`, `6h`, `8h`,
+    ///   `12h`, `1d`, `3d`, `1w`, `1M`
+    async fn subscribe_candlestick_raw(&self, _symbol_interval_list: &[(String, String)]) {
+        panic!("Native candlestick intervals are NOT supported by this exchange");
+    }
+
+    /// Subscribes to level2 orderbook channels with the given depth and
+    /// update interval.
+    ///
+    /// `depth` selects the kind of channel:
+    ///
+    /// * `None` subscribes to incremental updates, `subscribe_l2(symbols,
+    ///   None, None)` is the same as `subscribe_orderbook(symbols)`
+    /// * `Some(k)` subscribes to top-k snapshots instead of incremental
+    ///   updates, `subscribe_l2(symbols, Some(k), None)` is the same as
+    ///   `subscribe_orderbook_topk_with_depth(symbols, k)`
+    ///
+    /// `interval_ms` of `None` falls back to the exchange default. Exchanges
+    /// that don't support choosing depth or interval will panic.
+    ///
+    /// * Binance diff depth `depth` and `depth@100ms`, partial book depth
+    ///   `depth5`, `depth10`, `depth20`, `depth20@100ms`, etc.
+    async fn subscribe_l2(
+        &self,
+        symbols: &[String],
+        depth: Option<usize>,
+        interval_ms: Option<u64>,
+    ) {
+        if depth.is_some() || interval_ms.is_some() {
+            panic!("Choosing orderbook depth or interval is NOT supported by this exchange");
+        }
+        self.subscribe_orderbook(symbols).await;
+    }
+
+    /// Subscribes to index price channels.
+    ///
+    /// Only a few derivative markets push index prices in a separate
+    /// channel, calling this function with other exchanges will panic.
+    ///
+    /// * Binance COIN-margined `<pair>@indexPrice@1s`, e.g., `BTCUSD`
+    async fn subscribe_index_price(&self, _symbols: &[String]) {
+        panic!("The index price channel is NOT supported by this exchange");
+    }
+
+    /// Subscribes to mark price channels.
+    ///
+    /// Calling this function with exchanges that don't have it will panic.
+    ///
+    /// * Binance futures `<symbol>@markPrice@1s`
+    async fn subscribe_mark_price(&self, _symbols: &[String]) {
+        panic!("The mark price channel is NOT supported by this exchange");
+    }
+
+    /// Subscribes to funding rate channels of perpetual swap markets.
+    ///
+    /// Calling this function with spot clients or exchanges that don't have
+    /// it will panic.
+    ///
+    /// * Binance futures `<symbol>@markPrice@1s`, which carries the funding rate
+    /// * MEXC Swap `funding.rate`
+    async fn subscribe_funding_rate(&self, _symbols: &[String]) {
+        panic!("The funding rate channel is NOT supported by this exchange");
+    }
+
+    /// Subscribes to the all-market trade channel.
+    ///
+    /// Only a few exchanges push trades of all symbols in one channel,
+    /// calling this function with other exchanges will panic.
+    ///
+    /// * BitMEX `trade` without symbol
+    async fn subscribe_all_trade(&self) {
+        panic!("The all-market trade channel is NOT supported by this exchange");
+    }
+
+    /// Subscribes to the all-market BBO channel.
+    ///
+    /// Calling this function with exchanges that don't have it will panic.
+    ///
+    /// * Binance `!bookTicker`, only available in contract markets
+    /// * BitMEX `quote` without symbol
+    async fn subscribe_all_bbo(&self) {
+        panic!("The all-market BBO channel is NOT supported by this exchange");
+    }
+
+    /// Subscribes to the all-market 24hr rolling window ticker channel.
+    ///
+    /// Calling this function with exchanges that don't have it will panic.
+    ///
+    /// * Binance `!ticker@arr`
+    /// * MEXC Spot `overview`
+    async fn subscribe_all_ticker(&self) {
+        panic!("The all-market ticker channel is NOT supported by this exchange");
+    }
+
     /// Subscribe to multiple topics.
     ///
     /// topic = channel + symbol, a topic will be converted to an
@@ -123,19 +336,92 @@ pub trait WSClient {
     ///   "ETH-USDT".to_string())]`
     async fn subscribe(&self, topics: &[(String, String)]);
 
+    /// Subscribes to multiple topics with typed channels.
+    ///
+    /// Unlike `subscribe()`, a typo in the channel fails at compile time.
+    /// Channels that the exchange doesn't have will panic.
+    ///
+    /// * Binance: `vec![(Channel::AggTrade, "BTCUSDT".to_string()),
+    ///   (Channel::Kline(60), "BTCUSDT".to_string())]`
+    async fn subscribe_typed(&self, _topics: &[(Channel, String)]) {
+        panic!("Typed channels are NOT supported by this exchange");
+    }
+
     /// Unsubscribes multiple topics.
     ///
     /// topic = channel + symbol
     async fn unsubscribe(&self, topics: &[(String, String)]);
 
+    /// Returns the topics currently subscribed, sorted by channel and symbol.
+    ///
+    /// Topics passed to `subscribe()` and `unsubscribe()` are tracked, so are
+    /// the ones of `subscribe_trade()`, `subscribe_orderbook()`,
+    /// `subscribe_typed()`, etc., which call `subscribe()`. Candlesticks are
+    /// returned by `candlestick_subscriptions()` instead.
+    ///
+    /// Methods that send raw commands are NOT tracked, though subscriptions
+    /// of all but `send()` are sent again after reconnecting:
+    ///
+    /// * `send()`
+    /// * `subscribe_all_trade()`, `subscribe_all_bbo()` and
+    ///   `subscribe_all_ticker()`, which have no symbol
+    /// * `subscribe_orderbook_topk_with_depth()` of MEXC
+    /// * `subscribe_orderbook()` and `subscribe_l3_orderbook()` of Bitfinex
+    /// * `subscribe_orderbook()` of Huobi futures and swap markets
+    ///
+    /// The default implementation tracks nothing and returns an empty list.
+    fn subscriptions(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Returns the candlesticks subscribed by `subscribe_candlestick()` and
+    /// `subscribe_typed()`, sorted by symbol and interval in seconds, empty by
+    /// default.
+    fn candlestick_subscriptions(&self) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+
     /// Send raw JSON commands.
     ///
-    /// This is a low-level API for advanced users only.
+    /// This is a low-level API for advanced users only. Commands sent while
+    /// the client is reconnecting are queued and sent after reconnecting.
     async fn send(&self, commands: &[String]);
 
-    /// Starts the infinite event loop.
-    async fn run(&self);
+    /// Starts the event loop.
+    ///
+    /// * `Some(duration)` runs the event loop for `duration`, then closes the
+    ///   connection and returns, which is handy to collect a bounded sample of
+    ///   messages.
+    /// * `None` runs until `close()` is called or the consumer is dropped.
+    ///
+    /// After the connection is lost, the client reconnects up to
+    /// `WSClientBuilder::max_reconnects()` times and subscribes again to
+    /// everything subscribed before, then returns `WSError::GaveUp` if all
+    /// attempts failed. Clients created by `new()` don't reconnect.
+    async fn run(&self, duration: Option<std::time::Duration>) -> Result<(), WSError>;
+
+    /// Returns parameters of the underlying connection, useful to find out
+    /// which endpoint or region the client actually landed on.
+    fn connection_info(&self) -> ConnectionInfo;
+
+    /// Returns the number of messages dropped because the consumer fell
+    /// behind, always 0 unless the client was created by
+    /// `WSClientBuilder::build_with_receiver()` with a dropping `BackPressure`.
+    fn dropped_messages(&self) -> u64 {
+        self.metrics().dropped_messages
+    }
+
+    /// Returns a snapshot of cumulative counters, e.g., a health check can
+    /// report a stalled connection if `last_message_at` is too old. The
+    /// default implementation returns all zeros.
+    fn metrics(&self) -> WSClientMetrics {
+        WSClientMetrics::default()
+    }
 
     /// Close the connection and break the loop in Run().
+    ///
+    /// Sends a CloseFrame, waits up to 5 seconds for the server's close
+    /// acknowledgment and stops the heartbeat task, so that no half-open
+    /// connection is left on the exchange side.
     async fn close(&self);
 }
@@ -0,0 +1,325 @@
+use async_trait::async_trait;
+use std::{
+    num::NonZeroU32,
+    sync::{mpsc::Sender, Arc},
+    time::Duration,
+};
+
+use super::message_queue::{message_queue, BackPressure, MessageReceiver, QueueSender};
+
+/// Default capacity and policy of the queue in front of a `MessageReceiver`.
+const DEFAULT_BACK_PRESSURE: (usize, BackPressure) = (1024, BackPressure::Block);
+
+/// Accumulates options of a websocket client.
+///
+/// Options that are not set fall back to the defaults of each exchange, so
+/// `WSClientBuilder::new().build::<BinanceSpotWSClient>(tx)` is the same as
+/// `BinanceSpotWSClient::new(tx, None)`.
+///
+/// ```no_run
+/// use crypto_ws_client::{BackPressure, BinanceSpotWSClient, WSClientBuilder};
+/// use std::time::Duration;
+///
+/// # async fn example() {
+/// let (ws_client, rx) = WSClientBuilder::new()
+///     .url("wss://stream.binance.com:443/stream")
+///     .proxy("socks5://127.0.0.1:9050")
+///     .rate_limit(3.try_into().unwrap(), Duration::from_secs(1))
+///     .heartbeat_interval(Duration::from_secs(30))
+///     .back_pressure(1024, BackPressure::DropOldest)
+///     .build_with_receiver::<BinanceSpotWSClient>()
+///     .await;
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct WSClientBuilder {
+    pub(crate) url: Option<String>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) uplink_limit: Option<(NonZeroU32, Duration)>,
+    pub(crate) heartbeat_interval: Option<u64>, // seconds
+    pub(crate) back_pressure: Option<(usize, BackPressure)>,
+    pub(crate) max_reconnects: Option<u32>,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) jitter_seed: Option<u64>,
+    pub(crate) tee: Option<Sender<String>>,
+    pub(crate) queue_tx: Option<Arc<QueueSender<String>>>, // set by build_with_receiver()
+}
+
+impl Default for WSClientBuilder {
+    fn default() -> Self {
+        WSClientBuilder {
+            url: None,
+            proxy: None,
+            uplink_limit: None,
+            heartbeat_interval: None,
+            back_pressure: None,
+            max_reconnects: Some(0),
+            connect_timeout: None,
+            idle_timeout: None,
+            jitter_seed: None,
+            tee: None,
+            queue_tx: None,
+        }
+    }
+}
+
+impl WSClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with_url(url: Option<&str>) -> Self {
+        WSClientBuilder { url: url.map(|url| url.to_string()), ..Default::default() }
+    }
+
+    /// Overrides the server url, usually you don't need to specify it.
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// Connects through a SOCKS5 proxy, e.g., `socks5://127.0.0.1:9050`.
+    ///
+    /// Without it the `https_proxy` and `http_proxy` environment variables
+    /// are used.
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// Sends at most `max_messages` uplink messages per `period`.
+    pub fn rate_limit(mut self, max_messages: NonZeroU32, period: Duration) -> Self {
+        self.uplink_limit = Some((max_messages, period));
+        self
+    }
+
+    /// Overrides the heartbeat interval of exchanges that require clients to
+    /// send pings.
+    pub fn heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = Some(interval.as_secs());
+        self
+    }
+
+    /// Sets the capacity of the queue in front of the `MessageReceiver`
+    /// returned by `build_with_receiver()`, and what to do when it is full
+    /// because the consumer can't keep up, defaults to 1024 messages with
+    /// `BackPressure::Block`.
+    ///
+    /// Dropped messages are counted by `WSClient::dropped_messages()`. Only
+    /// valid with `build_with_receiver()`, a `std::sync::mpsc::channel` passed
+    /// to `build()` is unbounded.
+    pub fn back_pressure(mut self, capacity: usize, policy: BackPressure) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        self.back_pressure = Some((capacity, policy));
+        self
+    }
+
+    /// Sets how many times to retry when connecting fails, and to reconnect
+    /// after the connection is lost, `None` retries forever, defaults to
+    /// `Some(0)`.
+    ///
+    /// Retries wait for a linear backoff plus random jitter. After the last
+    /// attempt fails the client gives up and `WSClient::run()` returns
+    /// `WSError::GaveUp`, so that a supervisor can recreate it.
+    pub fn max_reconnects(mut self, max_reconnects: Option<u32>) -> Self {
+        self.max_reconnects = max_reconnects;
+        self
+    }
+
+    /// Sets the max time of the TCP connection, TLS and websocket handshakes,
+    /// defaults to 30 seconds.
+    ///
+    /// A timed out attempt counts as a failed one, see `max_reconnects()`.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Treats the connection as dead if nothing is received for `timeout`,
+    /// then closes it and reconnects, see `max_reconnects()`.
+    ///
+    /// Defaults to three times the heartbeat interval for exchanges that
+    /// require clients to send pings, and to 5 minutes for other exchanges,
+    /// e.g., Binance, whose servers ping clients every few minutes.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Seeds the random jitter added to reconnect delays, which makes the
+    /// delays reproducible in tests, defaults to a seed from the OS.
+    pub fn jitter_seed(mut self, seed: u64) -> Self {
+        self.jitter_seed = Some(seed);
+        self
+    }
+
+    /// Sends a copy of every text frame received, including frames that are
+    /// not dispatched to `tx`, e.g., subscription responses and pongs.
+    ///
+    /// Each copy is prefixed by the receive time in milliseconds since UNIX
+    /// epoch and a tab, e.g., `1676000000000\t{"stream":...}`, which is handy
+    /// for debugging and recording replay files.
+    pub fn tee(mut self, tee: Sender<String>) -> Self {
+        self.tee = Some(tee);
+        self
+    }
+
+    /// Connects and returns the concrete websocket client.
+    ///
+    /// * `tx` - The sending part of a channel
+    pub async fn build<C: FromWSClientBuilder>(self, tx: Sender<String>) -> C {
+        assert!(
+            self.back_pressure.is_none(),
+            "back_pressure() needs a bounded queue, use build_with_receiver() instead"
+        );
+        C::from_builder(self, tx).await
+    }
+
+    /// Connects and returns the concrete websocket client, along with the
+    /// receiving end of a bounded queue of messages, see `back_pressure()`.
+    pub async fn build_with_receiver<C: FromWSClientBuilder>(mut self) -> (C, MessageReceiver) {
+        let (capacity, policy) = self.back_pressure.unwrap_or(DEFAULT_BACK_PRESSURE);
+        let (queue_tx, queue_rx) = message_queue::<String>(capacity, policy);
+        self.queue_tx = Some(Arc::new(queue_tx));
+        // messages go to queue_tx instead
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let ws_client = C::from_builder(self, tx).await;
+        (ws_client, MessageReceiver::new(queue_rx))
+    }
+}
+
+/// Websocket clients which can be created by a `WSClientBuilder`.
+#[async_trait]
+pub trait FromWSClientBuilder: Sized {
+    async fn from_builder(builder: WSClientBuilder, tx: Sender<String>) -> Self;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WSClientBuilder;
+    use crate::{BackPressure, BitmexWSClient, WSClient, WSError};
+    use futures_util::StreamExt;
+    use nonzero_ext::nonzero;
+    use std::time::Duration;
+
+    #[test]
+    fn test_options() {
+        let builder = WSClientBuilder::new()
+            .url("wss://example.com/ws")
+            .proxy("socks5://127.0.0.1:9050")
+            .rate_limit(nonzero!(3u32), Duration::from_secs(1))
+            .heartbeat_interval(Duration::from_secs(15))
+            .back_pressure(8, BackPressure::DropNewest)
+            .max_reconnects(None)
+            .connect_timeout(Duration::from_secs(5))
+            .idle_timeout(Duration::from_secs(90))
+            .jitter_seed(7);
+        assert_eq!(Some("wss://example.com/ws"), builder.url.as_deref());
+        assert_eq!(Some("socks5://127.0.0.1:9050"), builder.proxy.as_deref());
+        assert_eq!(Some((nonzero!(3u32), Duration::from_secs(1))), builder.uplink_limit);
+        assert_eq!(Some(15), builder.heartbeat_interval);
+        assert_eq!(Some((8, BackPressure::DropNewest)), builder.back_pressure);
+        assert_eq!(None, builder.max_reconnects);
+        assert_eq!(Some(Duration::from_secs(5)), builder.connect_timeout);
+        assert_eq!(Some(Duration::from_secs(90)), builder.idle_timeout);
+        assert_eq!(Some(7), builder.jitter_seed);
+
+        let builder = WSClientBuilder::with_url(None);
+        assert!(builder.url.is_none());
+        assert_eq!(Some(0), builder.max_reconnects);
+        assert!(builder.uplink_limit.is_none());
+        assert!(builder.idle_timeout.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_build() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws_stream.next().await {}
+        });
+
+        let url = format!("ws://{addr}");
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let ws_client = WSClientBuilder::new().url(&url).build::<BitmexWSClient>(tx).await;
+        assert_eq!(url, ws_client.connection_info().url);
+        assert_eq!(0, ws_client.dropped_messages());
+        ws_client.close().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_slow_consumer() {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // the server pushes 100 trades at once
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for i in 0..100 {
+                let trade = format!(r#"{{"table":"trade","action":"insert","data":[{i}]}}"#);
+                ws_stream.send(Message::Text(trade)).await.unwrap();
+            }
+            while let Some(Ok(_)) = ws_stream.next().await {}
+        });
+
+        let (ws_client, rx) = WSClientBuilder::new()
+            .url(&format!("ws://{addr}"))
+            .back_pressure(4, BackPressure::DropOldest)
+            .build_with_receiver::<BitmexWSClient>()
+            .await;
+        // the consumer starts draining after all trades arrived
+        let consumer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(500));
+            rx.collect::<Vec<String>>()
+        });
+        ws_client.run(Some(Duration::from_secs(1))).await.unwrap();
+        let dropped = ws_client.dropped_messages();
+        drop(ws_client);
+
+        let received = consumer.join().unwrap();
+        assert!(dropped > 0);
+        assert_eq!(100, received.len() as u64 + dropped);
+        assert!(received.last().unwrap().contains("[99]"));
+    }
+
+    #[test]
+    #[should_panic(expected = "use build_with_receiver() instead")]
+    fn test_back_pressure_without_receiver() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let builder = WSClientBuilder::new().back_pressure(4, BackPressure::DropOldest);
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(builder.build::<BitmexWSClient>(tx));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_give_up() {
+        // the server never accepts, so every websocket handshake times out
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let ws_client = WSClientBuilder::new()
+            .url(&format!("ws://{addr}"))
+            .connect_timeout(Duration::from_millis(100))
+            .max_reconnects(Some(2))
+            .build::<BitmexWSClient>(tx)
+            .await;
+        assert_eq!(None, ws_client.connection_info().connected_at);
+        match ws_client.run(None).await {
+            Err(WSError::GaveUp { attempts, error }) => {
+                assert_eq!(3, attempts);
+                assert!(error.contains("timed out"), "{error}");
+            }
+            ret => panic!("expected GaveUp, got {ret:?}"),
+        }
+        // retries of the first connection count as well
+        assert_eq!(2, ws_client.metrics().reconnects);
+        drop(listener);
+    }
+}
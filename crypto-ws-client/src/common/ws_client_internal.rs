@@ -1,103 +1,403 @@
 use std::{
+    collections::BTreeSet,
     io::prelude::*,
     num::NonZeroU32,
     sync::{
-        atomic::{AtomicIsize, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicIsize, AtomicU64, Ordering},
+        Arc, Mutex,
     },
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use flate2::read::{DeflateDecoder, GzDecoder};
 use log::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use reqwest::StatusCode;
+use tokio::sync::{mpsc::Sender, Notify};
 use tokio_tungstenite::tungstenite::{Error, Message};
 
-use crate::common::message_handler::{MessageHandler, MiscMessage};
+use crate::common::{
+    command_translator::CommandTranslator,
+    message_handler::{MessageHandler, MiscMessage},
+    message_queue::{QueueReceiver, QueueSender},
+    ws_client::{ConnectionInfo, WSClientMetrics, WSError},
+    ws_client_builder::WSClientBuilder,
+};
+
+// Base delay between two connection attempts
+const RECONNECT_BACKOFF: Duration = Duration::from_millis(500);
+// Binance-style servers ping clients every few minutes
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+// Linear backoff capped at 10 times `RECONNECT_BACKOFF`, plus up to 50% random
+// jitter so that clients disconnected together don't reconnect together
+fn reconnect_backoff(attempts: u32, rng: &mut impl Rng) -> Duration {
+    let backoff = RECONNECT_BACKOFF * attempts.min(10);
+    backoff + Duration::from_millis(rng.gen_range(0..=backoff.as_millis() as u64 / 2))
+}
+
+// Counters shared by the event loop and `metrics()`
+#[derive(Default)]
+struct Counters {
+    messages_received: AtomicU64,
+    bytes_received: AtomicU64,
+    messages_dispatched: AtomicU64,
+    last_message_at: AtomicU64, // milliseconds since UNIX epoch, 0 if none
+}
+
+// Decodes a binary message, which is gzip or deflate compressed by some
+// exchanges, e.g., Huobi and OKX, or plain UTF-8 text
+fn decode_binary(binary: &[u8]) -> Option<String> {
+    let mut txt = String::new();
+    if binary.starts_with(&[0x1f, 0x8b]) {
+        GzDecoder::new(binary).read_to_string(&mut txt).ok().map(|_| txt)
+    } else if let Ok(plain) = std::str::from_utf8(binary) {
+        Some(plain.to_string())
+    } else {
+        DeflateDecoder::new(binary).read_to_string(&mut txt).ok().map(|_| txt)
+    }
+}
+
+// Hands messages over to the consumer
+enum Dispatcher {
+    Channel(std::sync::mpsc::Sender<String>),
+    // bounded, see `WSClientBuilder::build_with_receiver()`
+    Queue(Arc<QueueSender<String>>),
+}
+
+impl Dispatcher {
+    // Returns false if the consumer is gone
+    async fn send(&self, msg: String) -> bool {
+        match self {
+            Dispatcher::Channel(tx) => tx.send(msg).is_ok(),
+            Dispatcher::Queue(queue_tx) => queue_tx.send(msg).await,
+        }
+    }
+}
+
+// Connects to a websocket server, retrying with a linear backoff
+struct Connector {
+    url: String,
+    uplink_limit: Option<(NonZeroU32, Duration)>,
+    proxy: Option<String>,
+    connect_timeout: Option<Duration>,
+    max_reconnects: Option<u32>,
+    rng: Mutex<StdRng>,  // jitter of backoffs
+    attempts: AtomicU64, // all connection attempts, including the first one
+}
+
+impl Connector {
+    // Gives up after `max_attempts` failed attempts, `None` retries forever
+    async fn connect(
+        &self,
+        max_attempts: Option<u32>,
+    ) -> Result<(QueueReceiver<Message>, Sender<Message>, ConnectionInfo), WSError> {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            self.attempts.fetch_add(1, Ordering::Relaxed);
+            let err = match super::connect_async::connect_async(
+                &self.url,
+                self.uplink_limit,
+                self.proxy.as_deref(),
+                self.connect_timeout,
+            )
+            .await
+            {
+                Ok(ret) => return Ok(ret),
+                Err(err) => err,
+            };
+            if let Error::Http(resp) = &err {
+                if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+                    // a malformed header falls back to the backoff below
+                    let retry_after = resp.headers().get("retry-after");
+                    if let Some(retry_after) = retry_after.and_then(|v| v.to_str().ok()) {
+                        if let Ok(mut seconds) = retry_after.parse::<u64>() {
+                            // add random seconds to avoid concurrent requests
+                            seconds += self.rng.lock().unwrap().gen_range(1..10);
+                            error!(
+                                "The retry-after header value is {}, sleeping for {} seconds now",
+                                retry_after, seconds
+                            );
+                            tokio::time::sleep(Duration::from_secs(seconds)).await;
+                        }
+                    }
+                }
+            }
+            if max_attempts.map(|max| attempts >= max).unwrap_or(false) {
+                return Err(WSError::GaveUp { attempts, error: err.to_string() });
+            }
+            let backoff = reconnect_backoff(attempts, &mut *self.rng.lock().unwrap());
+            warn!("Failed to connect to {}, error: {}, retrying in {:?}", self.url, err, backoff);
+            tokio::time::sleep(backoff).await;
+        }
+    }
+}
 
 // `WSClientInternal` should be Sync + Send so that it can be put into Arc
 // directly.
 pub(crate) struct WSClientInternal<H: MessageHandler> {
     exchange: &'static str, // Eexchange name
     pub(crate) url: String, // Websocket base url
-    // pass parameters to run()
+    // pass parameters to run(), the error if connecting failed
     #[allow(clippy::type_complexity)]
     params_rx: std::sync::Mutex<
-        tokio::sync::oneshot::Receiver<(
-            H,
-            tokio::sync::mpsc::Receiver<Message>,
-            std::sync::mpsc::Sender<String>,
-        )>,
+        tokio::sync::oneshot::Receiver<(H, Result<QueueReceiver<Message>, WSError>, Dispatcher)>,
     >,
-    command_tx: tokio::sync::mpsc::Sender<Message>,
+    connector: Connector,
+    // the current connection, replaced after reconnecting
+    command_tx: Mutex<Sender<Message>>,
+    connection_info: Mutex<ConnectionInfo>,
+    heartbeat_interval: Option<u64>, // overrides the handler's interval
+    idle_timeout: Option<Duration>,
+    // set by close(), so that run() returns instead of reconnecting
+    closing: AtomicBool,
+    close_notify: Notify,
+    dropped_messages: Arc<AtomicU64>,
+    counters: Counters,
+    subscriptions: Mutex<BTreeSet<(String, String)>>, // (channel, symbol)
+    candlesticks: Mutex<BTreeSet<(String, usize)>>,   // (symbol, interval)
+    raw_subscriptions: Mutex<Vec<String>>,            // commands
+    pending_commands: Mutex<Vec<String>>,             // sent while disconnected
+    tee: Option<std::sync::mpsc::Sender<String>>,
 }
 
 impl<H: MessageHandler> WSClientInternal<H> {
+    // `uplink_limit` is the exchange default, options in `builder` take
+    // precedence over it
     pub async fn connect(
         exchange: &'static str,
         url: &str,
         handler: H,
         uplink_limit: Option<(NonZeroU32, std::time::Duration)>,
         tx: std::sync::mpsc::Sender<String>,
+        builder: &WSClientBuilder,
     ) -> Self {
         // A channel to send parameters to run()
         let (params_tx, params_rx) = tokio::sync::oneshot::channel::<(
             H,
-            tokio::sync::mpsc::Receiver<Message>,
-            std::sync::mpsc::Sender<String>,
+            Result<QueueReceiver<Message>, WSError>,
+            Dispatcher,
         )>();
 
-        match super::connect_async::connect_async(url, uplink_limit).await {
-            Ok((message_rx, command_tx)) => {
-                let _ = params_tx.send((handler, message_rx, tx));
-
-                WSClientInternal {
-                    exchange,
-                    url: url.to_string(),
-                    params_rx: std::sync::Mutex::new(params_rx),
-                    command_tx,
+        let connector = Connector {
+            url: url.to_string(),
+            uplink_limit: builder.uplink_limit.or(uplink_limit),
+            proxy: builder.proxy.clone(),
+            connect_timeout: builder.connect_timeout,
+            max_reconnects: builder.max_reconnects,
+            rng: Mutex::new(match builder.jitter_seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            }),
+            attempts: AtomicU64::new(0),
+        };
+        // connecting is retried up to `max_reconnects` times, then the client
+        // gives up and run() returns the error
+        let (message_rx, command_tx, connection_info) =
+            match connector.connect(builder.max_reconnects.map(|max| max + 1)).await {
+                Ok((message_rx, command_tx, connection_info)) => {
+                    debug!("Connected to {}, {:?}", url, connection_info);
+                    (Ok(message_rx), command_tx, connection_info)
                 }
-            }
-            Err(err) => match err {
-                Error::Http(resp) => {
-                    if resp.status() == StatusCode::TOO_MANY_REQUESTS {
-                        if let Some(retry_after) = resp.headers().get("retry-after") {
-                            let mut seconds = retry_after.to_str().unwrap().parse::<u64>().unwrap();
-                            seconds += rand::random::<u64>() % 9 + 1; // add random seconds to avoid concurrent requests
-                            error!(
-                                "The retry-after header value is {}, sleeping for {} seconds now",
-                                retry_after.to_str().unwrap(),
-                                seconds
-                            );
-                            tokio::time::sleep(Duration::from_secs(seconds)).await;
-                        }
-                    }
-                    panic!("Failed to connect to {url} due to 429 too many requests")
+                Err(err) => {
+                    error!("Failed to connect to {}, {}", url, err);
+                    let connection_info = ConnectionInfo {
+                        url: url.to_string(),
+                        connected_at: None,
+                        tls_version: None,
+                        remote_addr: None,
+                    };
+                    // commands sent to a closed channel are ignored
+                    (Err(err), tokio::sync::mpsc::channel(1).0, connection_info)
                 }
-                _ => panic!("Failed to connect to {url}, error: {err}"),
-            },
+            };
+
+        // only the queue in front of the consumer drops messages
+        let (dispatcher, dropped_messages) = match &builder.queue_tx {
+            Some(queue_tx) => (Dispatcher::Queue(queue_tx.clone()), queue_tx.dropped()),
+            None => (Dispatcher::Channel(tx), Arc::new(AtomicU64::new(0))),
+        };
+        let _ = params_tx.send((handler, message_rx, dispatcher));
+
+        WSClientInternal {
+            exchange,
+            url: url.to_string(),
+            params_rx: std::sync::Mutex::new(params_rx),
+            connector,
+            command_tx: Mutex::new(command_tx),
+            connection_info: Mutex::new(connection_info),
+            heartbeat_interval: builder.heartbeat_interval,
+            idle_timeout: builder.idle_timeout,
+            closing: AtomicBool::new(false),
+            close_notify: Notify::new(),
+            dropped_messages,
+            counters: Counters::default(),
+            subscriptions: Mutex::new(BTreeSet::new()),
+            candlesticks: Mutex::new(BTreeSet::new()),
+            raw_subscriptions: Mutex::new(Vec::new()),
+            pending_commands: Mutex::new(Vec::new()),
+            tee: builder.tee.clone(),
         }
     }
 
+    // Commands that can't be sent while disconnected are sent after
+    // reconnecting
     pub async fn send(&self, commands: &[String]) {
-        for command in commands {
+        let unsent = self.send_tracked(commands).await;
+        if !unsent.is_empty() {
+            debug!("Disconnected from {}, queued {} commands", self.url, unsent.len());
+            self.pending_commands.lock().unwrap().extend(unsent.iter().cloned());
+        }
+    }
+
+    // Sends commands of tracked subscriptions, which are not queued while
+    // disconnected because they are sent again after reconnecting, returns
+    // the unsent commands
+    pub async fn send_tracked<'a>(&self, commands: &'a [String]) -> &'a [String] {
+        let command_tx = self.command_tx.lock().unwrap().clone();
+        for (i, command) in commands.iter().enumerate() {
             debug!("{}", command);
-            if self.command_tx.send(Message::Text(command.to_string())).await.is_err() {
-                break; // break the loop if there is no receiver
+            if command_tx.send(Message::Text(command.to_string())).await.is_err() {
+                return &commands[i..]; // the connection is lost
             }
         }
+        &[]
     }
 
-    pub async fn run(&self) {
-        let (mut handler, mut message_rx, tx) = {
+    // Sends subscribe commands that can't be expressed as topics, which are
+    // sent again after reconnecting
+    pub async fn subscribe_raw(&self, commands: &[String]) {
+        self.raw_subscriptions.lock().unwrap().extend(commands.iter().cloned());
+        self.send_tracked(commands).await;
+    }
+
+    pub fn add_subscriptions(&self, topics: &[(String, String)]) {
+        self.subscriptions.lock().unwrap().extend(topics.iter().cloned());
+    }
+
+    pub fn remove_subscriptions(&self, topics: &[(String, String)]) {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        for topic in topics {
+            subscriptions.remove(topic);
+        }
+    }
+
+    pub fn subscriptions(&self) -> Vec<(String, String)> {
+        self.subscriptions.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn add_candlesticks(&self, symbol_interval_list: &[(String, usize)]) {
+        self.candlesticks.lock().unwrap().extend(symbol_interval_list.iter().cloned());
+    }
+
+    pub fn candlesticks(&self) -> Vec<(String, usize)> {
+        self.candlesticks.lock().unwrap().iter().cloned().collect()
+    }
+
+    // `translator` translates subscriptions to commands after reconnecting
+    pub async fn run(
+        &self,
+        duration: Option<Duration>,
+        translator: &(dyn CommandTranslator + Sync),
+    ) -> Result<(), WSError> {
+        match duration {
+            Some(duration) => {
+                match tokio::time::timeout(duration, self.run_loop(translator)).await {
+                    Ok(ret) => ret,
+                    Err(_) => {
+                        self.close().await;
+                        Ok(())
+                    }
+                }
+            }
+            None => self.run_loop(translator).await,
+        }
+    }
+
+    async fn run_loop(&self, translator: &(dyn CommandTranslator + Sync)) -> Result<(), WSError> {
+        let (mut handler, message_rx, tx) = {
             let mut guard = self.params_rx.lock().unwrap();
             guard.try_recv().unwrap()
         };
+        let mut message_rx = message_rx?;
+        debug!("Started the event loop of {}", self.url);
 
+        let ret = loop {
+            let Some(reason) = self.run_connection(&mut handler, &mut message_rx, &tx).await else {
+                break Ok(()); // the consumer is gone
+            };
+            if self.closing.load(Ordering::Acquire) {
+                break Ok(());
+            }
+            if self.connector.max_reconnects == Some(0) {
+                break Err(WSError::GaveUp { attempts: 0, error: reason });
+            }
+            warn!("Disconnected from {}, {}, reconnecting", self.url, reason);
+            let backoff = reconnect_backoff(1, &mut *self.connector.rng.lock().unwrap());
+            let reconnecting = async {
+                tokio::time::sleep(backoff).await;
+                self.connector.connect(self.connector.max_reconnects).await
+            };
+            let (new_message_rx, command_tx, connection_info) = tokio::select! {
+                ret = reconnecting => match ret {
+                    Ok(ret) => ret,
+                    Err(err) => break Err(err),
+                },
+                _ = self.close_notify.notified() => break Ok(()),
+            };
+            info!("Reconnected to {}, {:?}", self.url, connection_info);
+            message_rx = new_message_rx;
+            *self.command_tx.lock().unwrap() = command_tx.clone();
+            *self.connection_info.lock().unwrap() = connection_info;
+            if self.closing.load(Ordering::Acquire) {
+                // close() was called before command_tx got replaced
+                self.close_connection(&command_tx).await;
+                break Ok(());
+            }
+            self.resubscribe(translator).await;
+        };
+        debug!("The event loop of {} exited", self.url);
+        ret
+    }
+
+    // Sends subscriptions again after reconnecting, followed by commands
+    // queued while disconnected
+    async fn resubscribe(&self, translator: &(dyn CommandTranslator + Sync)) {
+        let topics = self.subscriptions();
+        let mut commands = Vec::new();
+        if !topics.is_empty() {
+            commands.extend(translator.translate_to_commands(true, &topics));
+        }
+        let candlesticks = self.candlesticks();
+        if !candlesticks.is_empty() {
+            commands.extend(translator.translate_to_candlestick_commands(true, &candlesticks));
+        }
+        commands.extend(self.raw_subscriptions.lock().unwrap().iter().cloned());
+        // not queued, otherwise they'd be sent twice after the next reconnect
+        self.send_tracked(&commands).await;
+        // then commands sent while disconnected, queued again if unsent
+        let pending = std::mem::take(&mut *self.pending_commands.lock().unwrap());
+        self.send(&pending).await;
+    }
+
+    // Handles messages of the current connection until it's lost, returns the
+    // reason, or None if the consumer is gone
+    async fn run_connection(
+        &self,
+        handler: &mut H,
+        message_rx: &mut QueueReceiver<Message>,
+        tx: &Dispatcher,
+    ) -> Option<String> {
+        let command_tx = self.command_tx.lock().unwrap().clone();
         let num_unanswered_ping = Arc::new(AtomicIsize::new(0)); // for debug only
+        let mut idle_timeout = self.idle_timeout.unwrap_or(DEFAULT_IDLE_TIMEOUT);
         if let Some((msg, interval)) = handler.get_ping_msg_and_interval() {
+            let interval = self.heartbeat_interval.unwrap_or(interval);
+            // pongs arrive at least once per interval on a live connection
+            idle_timeout = self.idle_timeout.unwrap_or(Duration::from_secs(interval * 3));
             // send heartbeat periodically
-            let command_tx_clone = self.command_tx.clone();
+            let command_tx_clone = command_tx.clone();
             let num_unanswered_ping_clone = num_unanswered_ping.clone();
             tokio::task::spawn(async move {
                 let mut timer = {
@@ -105,7 +405,14 @@ impl<H: MessageHandler> WSClientInternal<H> {
                     tokio::time::interval(duration)
                 };
                 loop {
-                    let now = timer.tick().await;
+                    let now = tokio::select! {
+                        now = timer.tick() => now,
+                        // stop the heartbeat after the connection is closed
+                        _ = command_tx_clone.closed() => {
+                            debug!("Stopped the heartbeat");
+                            break;
+                        }
+                    };
                     debug!("{:?} sending ping {}", now, msg.to_text().unwrap());
                     if let Err(err) = command_tx_clone.send(msg.clone()).await {
                         error!("Error sending ping {}", err);
@@ -116,47 +423,40 @@ impl<H: MessageHandler> WSClientInternal<H> {
             });
         }
 
-        while let Some(msg) = message_rx.recv().await {
+        loop {
+            let msg = match tokio::time::timeout(idle_timeout, message_rx.recv()).await {
+                Ok(msg) => msg,
+                Err(_) => {
+                    error!("Received nothing from {} for {:?}", self.url, idle_timeout);
+                    self.close_connection(&command_tx).await;
+                    return Some(format!("received nothing for {idle_timeout:?}"));
+                }
+            };
+            let Some(msg) = msg else { return Some("the connection was closed".to_string()) };
+            self.counters.messages_received.fetch_add(1, Ordering::Relaxed);
+            self.counters.bytes_received.fetch_add(msg.len() as u64, Ordering::Relaxed);
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+            self.counters.last_message_at.store(now, Ordering::Relaxed);
             let txt = match msg {
                 Message::Text(txt) => Some(txt),
                 Message::Binary(binary) => {
-                    let mut txt = String::new();
-                    let resp = match self.exchange {
-                        crate::clients::huobi::EXCHANGE_NAME
-                        | crate::clients::binance::EXCHANGE_NAME
-                        | "bitget"
-                        | "bitz" => {
-                            let mut decoder = GzDecoder::new(&binary[..]);
-                            decoder.read_to_string(&mut txt)
-                        }
-                        crate::clients::okx::EXCHANGE_NAME => {
-                            let mut decoder = DeflateDecoder::new(&binary[..]);
-                            decoder.read_to_string(&mut txt)
-                        }
-                        _ => {
-                            panic!("Unknown binary format from {}", self.url);
-                        }
-                    };
-
-                    match resp {
-                        Ok(_) => Some(txt),
-                        Err(err) => {
-                            error!("Decompression failed, {}", err);
-                            None
-                        }
+                    let txt = decode_binary(&binary);
+                    if txt.is_none() {
+                        error!("Failed to decode a binary message from {}", self.url);
                     }
+                    txt
                 }
                 Message::Ping(resp) => {
                     // binance server will send a ping frame every 3 or 5 minutes
                     debug!(
                         "Received a ping frame: {} from {}",
-                        std::str::from_utf8(&resp).unwrap(),
+                        String::from_utf8_lossy(&resp),
                         self.url,
                     );
                     if self.exchange == "binance" {
                         // send a pong frame
                         debug!("Sending a pong frame to {}", self.url);
-                        _ = self.command_tx.send(Message::Pong(Vec::new())).await;
+                        _ = command_tx.send(Message::Pong(Vec::new())).await;
                     }
                     None
                 }
@@ -164,14 +464,18 @@ impl<H: MessageHandler> WSClientInternal<H> {
                     num_unanswered_ping.store(0, Ordering::Release);
                     debug!(
                         "Received a pong frame: {} from {}, reset num_unanswered_ping to {}",
-                        std::str::from_utf8(&resp).unwrap(),
+                        String::from_utf8_lossy(&resp),
                         self.exchange,
                         num_unanswered_ping.load(Ordering::Acquire)
                     );
                     None
                 }
-                Message::Frame(_) => todo!(),
+                Message::Frame(_) => {
+                    warn!("Received a raw frame from {}", self.url);
+                    None
+                }
                 Message::Close(resp) => {
+                    // the connection ends after the close handshake
                     match resp {
                         Some(frame) => {
                             warn!(
@@ -181,22 +485,41 @@ impl<H: MessageHandler> WSClientInternal<H> {
                         }
                         None => warn!("Received a close message without CloseFrame"),
                     }
-                    // break;
-                    panic!("Received a CloseFrame"); //fail fast so that pm2 can restart the process
+                    None
                 }
             };
 
             if let Some(txt) = txt {
                 let txt = txt.as_str().trim().to_string();
-                match handler.handle_message(&txt) {
+                if let Some(tee) = &self.tee {
+                    _ = tee.send(format!("{now}\t{txt}"));
+                }
+                let mut misc_msg = handler.handle_message(&txt);
+                while let MiscMessage::Sleep(duration, inner) = misc_msg {
+                    debug!("Pausing the event loop of {} for {:?}", self.url, duration);
+                    tokio::time::sleep(duration).await;
+                    misc_msg = *inner;
+                }
+                match misc_msg {
                     MiscMessage::Normal => {
                         // the receiver might get dropped earlier than this loop
-                        if tx.send(txt).is_err() {
-                            break; // break the loop if there is no receiver
+                        if !tx.send(txt).await {
+                            warn!(
+                                "The receiver is dropped, exiting the event loop of {}",
+                                self.url
+                            );
+                            return None; // break the loop if there is no receiver
                         }
+                        self.counters.messages_dispatched.fetch_add(1, Ordering::Relaxed);
                     }
-                    MiscMessage::Mutated(txt) => _ = tx.send(txt),
-                    MiscMessage::WebSocket(ws_msg) => _ = self.command_tx.send(ws_msg).await,
+                    MiscMessage::Mutated(txt) => {
+                        if tx.send(txt).await {
+                            self.counters.messages_dispatched.fetch_add(1, Ordering::Relaxed);
+                        } else {
+                            warn!("The receiver is dropped, dropped a message from {}", self.url);
+                        }
+                    }
+                    MiscMessage::WebSocket(ws_msg) => _ = command_tx.send(ws_msg).await,
                     MiscMessage::Pong => {
                         num_unanswered_ping.store(0, Ordering::Release);
                         debug!(
@@ -206,16 +529,506 @@ impl<H: MessageHandler> WSClientInternal<H> {
                             num_unanswered_ping.load(Ordering::Acquire)
                         );
                     }
-                    MiscMessage::Reconnect => break, /* fail fast, pm2 will restart, restart is */
-                    // reconnect
-                    MiscMessage::Other => (), // ignore
+                    MiscMessage::Reconnect => {
+                        warn!("{} asked to reconnect", self.url);
+                        self.close_connection(&command_tx).await;
+                        return Some("the server asked to reconnect".to_string());
+                    }
+                    MiscMessage::Other | MiscMessage::Sleep(..) => (), // ignore
                 }
             }
         }
     }
 
+    pub fn connection_info(&self) -> ConnectionInfo {
+        self.connection_info.lock().unwrap().clone()
+    }
+
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped_messages.load(Ordering::Relaxed)
+    }
+
+    pub fn metrics(&self) -> WSClientMetrics {
+        let last_message_at = match self.counters.last_message_at.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(UNIX_EPOCH + Duration::from_millis(millis)),
+        };
+        WSClientMetrics {
+            messages_received: self.counters.messages_received.load(Ordering::Relaxed),
+            bytes_received: self.counters.bytes_received.load(Ordering::Relaxed),
+            messages_dispatched: self.counters.messages_dispatched.load(Ordering::Relaxed),
+            dropped_messages: self.dropped_messages(),
+            reconnects: self.connector.attempts.load(Ordering::Relaxed).saturating_sub(1),
+            last_message_at,
+        }
+    }
+
     pub async fn close(&self) {
-        // close the websocket connection and break the while loop in run()
-        _ = self.command_tx.send(Message::Close(None)).await;
+        // stop reconnecting and break the while loop in run()
+        self.closing.store(true, Ordering::Release);
+        self.close_notify.notify_one();
+        let command_tx = self.command_tx.lock().unwrap().clone();
+        self.close_connection(&command_tx).await;
+    }
+
+    // Closes a websocket connection
+    async fn close_connection(&self, command_tx: &Sender<Message>) {
+        if command_tx.send(Message::Close(None)).await.is_ok() {
+            // resolves after the event loop in connect_async() has exited,
+            // which waits for the server's close acknowledgment
+            let timeout = super::connect_async::CLOSE_TIMEOUT + Duration::from_secs(1);
+            if tokio::time::timeout(timeout, command_tx.closed()).await.is_err() {
+                warn!("Timed out closing the connection to {}", self.url);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BitmexWSClient, WSClient, WSClientBuilder, WSError};
+    use futures_util::{SinkExt, StreamExt};
+    use std::{sync::Arc, time::Duration};
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[test]
+    fn test_reconnect_backoff() {
+        use super::reconnect_backoff;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let delays = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (1..=12).map(|attempts| reconnect_backoff(attempts, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(delays(42), delays(42));
+        for (attempts, delay) in (1..=12).zip(delays(42)) {
+            let backoff = super::RECONNECT_BACKOFF * attempts.min(10);
+            assert!(delay >= backoff && delay <= backoff * 3 / 2);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_metrics() {
+        let trade = r#"{"table":"trade","action":"insert","data":[]}"#;
+        let info = r#"{"info":"Welcome to the BitMEX Realtime API."}"#;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws_stream.send(Message::Text(info.to_string())).await.unwrap();
+            ws_stream.send(Message::Text(trade.to_string())).await.unwrap();
+            while let Some(Ok(_)) = ws_stream.next().await {}
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ws_client = BitmexWSClient::new(tx, Some(&format!("ws://{addr}"))).await;
+        let metrics = ws_client.metrics();
+        assert_eq!(0, metrics.messages_received);
+        assert!(metrics.last_message_at.is_none());
+
+        ws_client.run(Some(Duration::from_secs(1))).await.unwrap();
+        let metrics = ws_client.metrics();
+        assert_eq!(2, metrics.messages_received);
+        assert_eq!((trade.len() + info.len()) as u64, metrics.bytes_received);
+        assert_eq!(1, metrics.messages_dispatched);
+        assert_eq!(0, metrics.dropped_messages);
+        assert_eq!(0, metrics.reconnects);
+        assert!(metrics.last_message_at.is_some());
+        assert_eq!(trade, rx.recv().unwrap());
+    }
+
+    fn compress(txt: &str, gzip: bool) -> Vec<u8> {
+        use flate2::{
+            write::{DeflateEncoder, GzEncoder},
+            Compression,
+        };
+        use std::io::Write;
+
+        if gzip {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(txt.as_bytes()).unwrap();
+            encoder.finish().unwrap()
+        } else {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(txt.as_bytes()).unwrap();
+            encoder.finish().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_decode_binary() {
+        let txt = r#"{"table":"trade","action":"insert","data":[]}"#;
+        assert_eq!(Some(txt), super::decode_binary(&compress(txt, true)).as_deref());
+        assert_eq!(Some(txt), super::decode_binary(&compress(txt, false)).as_deref());
+        assert_eq!(Some(txt), super::decode_binary(txt.as_bytes()).as_deref());
+        // invalid UTF-8 and truncated frames
+        assert_eq!(None, super::decode_binary(&[0xff, 0xfe, 0xfd]));
+        let gzip = compress(txt, true);
+        assert_eq!(None, super::decode_binary(&gzip[..gzip.len() / 2]));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_binary_messages() {
+        let trade = r#"{"table":"trade","action":"insert","data":[]}"#;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws_stream.send(Message::Binary(vec![0xff, 0xfe, 0xfd])).await.unwrap();
+            ws_stream.send(Message::Binary(compress(trade, true))).await.unwrap();
+            while let Some(Ok(_)) = ws_stream.next().await {}
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ws_client = BitmexWSClient::new(tx, Some(&format!("ws://{addr}"))).await;
+        ws_client.run(Some(Duration::from_secs(1))).await.unwrap();
+        assert_eq!(2, ws_client.metrics().messages_received);
+        assert_eq!(trade, rx.try_recv().unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tee() {
+        let trade = r#"{"table":"trade","action":"insert","data":[]}"#;
+        let info = r#"{"info":"Welcome to the BitMEX Realtime API."}"#;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws_stream.send(Message::Text(info.to_string())).await.unwrap();
+            ws_stream.send(Message::Text(trade.to_string())).await.unwrap();
+            while let Some(Ok(_)) = ws_stream.next().await {}
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (tee_tx, tee_rx) = std::sync::mpsc::channel();
+        let ws_client = WSClientBuilder::new()
+            .url(&format!("ws://{addr}"))
+            .tee(tee_tx)
+            .build::<BitmexWSClient>(tx)
+            .await;
+        ws_client.run(Some(Duration::from_secs(1))).await.unwrap();
+        drop(ws_client);
+
+        assert_eq!(vec![trade.to_string()], rx.iter().collect::<Vec<String>>());
+        let frames = tee_rx.iter().collect::<Vec<String>>();
+        assert_eq!(2, frames.len());
+        for (frame, expected) in frames.iter().zip([info, trade]) {
+            let (timestamp, txt) = frame.split_once('\t').unwrap();
+            assert!(timestamp.parse::<u64>().unwrap() > 0);
+            assert_eq!(expected, txt);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_current_thread() {
+        let trade = r#"{"table":"trade","action":"insert","data":[]}"#;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            // Replies to the subscribe command, the client and the server share one thread
+            if let Some(Ok(Message::Text(_))) = ws_stream.next().await {
+                ws_stream.send(Message::Text(trade.to_string())).await.unwrap();
+            }
+            while let Some(Ok(_)) = ws_stream.next().await {}
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ws_client = BitmexWSClient::new(tx, Some(&format!("ws://{addr}"))).await;
+        ws_client.subscribe_trade(&["XBTUSD".to_string()]).await;
+        ws_client.run(Some(Duration::from_secs(1))).await.unwrap();
+        assert_eq!(trade, rx.try_recv().unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_subscriptions() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws_stream.next().await {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let ws_client = BitmexWSClient::new(tx, Some(&format!("ws://{addr}"))).await;
+        ws_client.subscribe_trade(&["XBTUSD".to_string(), "ETHUSD".to_string()]).await;
+        ws_client.subscribe(&[("trade".to_string(), "XBTUSD".to_string())]).await;
+        assert_eq!(
+            vec![
+                ("trade".to_string(), "ETHUSD".to_string()),
+                ("trade".to_string(), "XBTUSD".to_string())
+            ],
+            ws_client.subscriptions()
+        );
+
+        ws_client.unsubscribe(&[("trade".to_string(), "ETHUSD".to_string())]).await;
+        assert_eq!(vec![("trade".to_string(), "XBTUSD".to_string())], ws_client.subscriptions());
+
+        ws_client
+            .subscribe_candlestick(&[("XBTUSD".to_string(), 3600), ("XBTUSD".to_string(), 60)])
+            .await;
+        ws_client.subscribe_candlestick(&[("XBTUSD".to_string(), 60)]).await;
+        assert_eq!(
+            vec![("XBTUSD".to_string(), 60), ("XBTUSD".to_string(), 3600)],
+            ws_client.candlestick_subscriptions()
+        );
+        // candlesticks are not topics
+        assert_eq!(vec![("trade".to_string(), "XBTUSD".to_string())], ws_client.subscriptions());
+        ws_client.close().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unsubscribe_from_consumer() {
+        // the server pushes a trade of every subscribed topic each 10ms
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let mut topics = std::collections::BTreeSet::new();
+            let mut interval = tokio::time::interval(Duration::from_millis(10));
+            loop {
+                tokio::select! {
+                    msg = ws_stream.next() => match msg {
+                        Some(Ok(Message::Text(txt))) => {
+                            let Ok(command) = serde_json::from_str::<serde_json::Value>(&txt)
+                            else { continue };
+                            for topic in command["args"].as_array().unwrap() {
+                                let topic = topic.as_str().unwrap().to_string();
+                                if command["op"] == "subscribe" {
+                                    topics.insert(topic);
+                                } else {
+                                    topics.remove(&topic);
+                                }
+                            }
+                        }
+                        Some(Ok(_)) => (),
+                        _ => break,
+                    },
+                    _ = interval.tick() => {
+                        for topic in topics.iter() {
+                            let symbol = topic.split(':').nth(1).unwrap();
+                            let trade = format!(
+                                r#"{{"table":"trade","action":"insert","data":[{{"symbol":"{symbol}"}}]}}"#
+                            );
+                            if ws_stream.send(Message::Text(trade)).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ws_client = Arc::new(BitmexWSClient::new(tx, Some(&format!("ws://{addr}"))).await);
+        ws_client.subscribe_trade(&["XBTUSD".to_string(), "ETHUSD".to_string()]).await;
+
+        // the consumer unsubscribes XBTUSD upon its first trade
+        let handle = tokio::runtime::Handle::current();
+        let ws_client_clone = ws_client.clone();
+        let consumer = tokio::task::spawn_blocking(move || {
+            let mut unsubscribed = false;
+            let mut received_after = Vec::new();
+            while let Ok(msg) = rx.recv_timeout(Duration::from_secs(1)) {
+                if unsubscribed {
+                    received_after.push(msg);
+                } else if msg.contains("XBTUSD") {
+                    let topic = ("trade".to_string(), "XBTUSD".to_string());
+                    handle.block_on(ws_client_clone.unsubscribe(&[topic]));
+                    unsubscribed = true;
+                }
+            }
+            received_after
+        });
+        ws_client.run(Some(Duration::from_secs(1))).await.unwrap();
+        ws_client.close().await;
+
+        // only trades already in flight are received after unsubscribing
+        let received_after = consumer.await.unwrap();
+        let last_xbtusd = received_after.iter().rposition(|msg| msg.contains("XBTUSD"));
+        assert!(received_after.len() - last_xbtusd.map_or(0, |i| i + 1) > 20);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_idle_timeout() {
+        // the server never sends anything after the handshake, and reports
+        // whether the client closed the connection
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws_stream.next().await {
+                if let Message::Close(_) = msg {
+                    return true;
+                }
+            }
+            false
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let ws_client = WSClientBuilder::new()
+            .url(&format!("ws://{addr}"))
+            .idle_timeout(Duration::from_millis(200))
+            .build::<BitmexWSClient>(tx)
+            .await;
+        let ret = tokio::time::timeout(Duration::from_secs(5), ws_client.run(None))
+            .await
+            .expect("run() should return after the idle timeout");
+        // max_reconnects is 0 by default
+        assert!(matches!(ret, Err(WSError::GaveUp { attempts: 0, .. })));
+        assert!(server.await.unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reconnect() {
+        // the server closes the first connection after a trade, and sends a
+        // trade of every subscribed topic on the second connection
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            if let Some(Ok(Message::Text(_))) = ws_stream.next().await {
+                let trade = r#"{"table":"trade","action":"insert","data":[1]}"#;
+                ws_stream.send(Message::Text(trade.to_string())).await.unwrap();
+            }
+            ws_stream.close(None).await.unwrap();
+            drop(ws_stream);
+
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws_stream.next().await {
+                if let Message::Text(txt) = msg {
+                    // skips pings
+                    let Ok(command) = serde_json::from_str::<serde_json::Value>(&txt) else {
+                        continue;
+                    };
+                    for topic in command["args"].as_array().unwrap() {
+                        let trade = format!(
+                            r#"{{"table":"trade","action":"insert","data":[{topic}]}}"#
+                        );
+                        ws_stream.send(Message::Text(trade)).await.unwrap();
+                    }
+                }
+            }
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ws_client = WSClientBuilder::new()
+            .url(&format!("ws://{addr}"))
+            .max_reconnects(Some(3))
+            .build::<BitmexWSClient>(tx)
+            .await;
+        ws_client.subscribe_trade(&["XBTUSD".to_string()]).await;
+        ws_client.run(Some(Duration::from_secs(3))).await.unwrap();
+        assert_ne!(None, ws_client.connection_info().connected_at);
+        assert_eq!(1, ws_client.metrics().reconnects);
+        drop(ws_client);
+
+        assert_eq!(
+            vec![
+                r#"{"table":"trade","action":"insert","data":[1]}"#.to_string(),
+                r#"{"table":"trade","action":"insert","data":["trade:XBTUSD"]}"#.to_string(),
+            ],
+            rx.iter().collect::<Vec<String>>()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reconnect_give_up() {
+        // the server closes the first connection, then refuses new ones
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws_stream.close(None).await.unwrap();
+            drop(ws_stream);
+            while let Ok((stream, _)) = listener.accept().await {
+                drop(stream);
+            }
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let ws_client = WSClientBuilder::new()
+            .url(&format!("ws://{addr}"))
+            .max_reconnects(Some(2))
+            .jitter_seed(42)
+            .build::<BitmexWSClient>(tx)
+            .await;
+        let ret = tokio::time::timeout(Duration::from_secs(10), ws_client.run(None))
+            .await
+            .expect("run() should give up by itself");
+        assert!(matches!(ret, Err(WSError::GaveUp { attempts: 2, .. })), "{ret:?}");
+        assert_eq!(2, ws_client.metrics().reconnects);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_subscribe_while_reconnecting() {
+        // the server drops the first connection, and sends a trade of every
+        // subscribed topic on the second connection
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            drop(tokio_tungstenite::accept_async(stream).await.unwrap());
+
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws_stream.next().await {
+                if let Message::Text(txt) = msg {
+                    // skips pings
+                    let Ok(command) = serde_json::from_str::<serde_json::Value>(&txt) else {
+                        continue;
+                    };
+                    for topic in command["args"].as_array().unwrap() {
+                        let trade = format!(
+                            r#"{{"table":"trade","action":"insert","data":[{topic}]}}"#
+                        );
+                        ws_stream.send(Message::Text(trade)).await.unwrap();
+                    }
+                }
+            }
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ws_client = Arc::new(
+            WSClientBuilder::new()
+                .url(&format!("ws://{addr}"))
+                .max_reconnects(Some(3))
+                .build::<BitmexWSClient>(tx)
+                .await,
+        );
+        let ws_client_clone = ws_client.clone();
+        let running = tokio::task::spawn(async move {
+            ws_client_clone.run(Some(Duration::from_secs(3))).await.unwrap();
+        });
+
+        // the client waits at least 500ms before reconnecting
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        ws_client.subscribe_trade(&["XBTUSD".to_string()]).await;
+        ws_client.send(&[r#"{"op":"subscribe","args":["trade:ETHUSD"]}"#.to_string()]).await;
+        running.await.unwrap();
+        drop(ws_client);
+
+        // each is received once, though sent before reconnecting
+        assert_eq!(
+            vec![
+                r#"{"table":"trade","action":"insert","data":["trade:XBTUSD"]}"#.to_string(),
+                r#"{"table":"trade","action":"insert","data":["trade:ETHUSD"]}"#.to_string(),
+            ],
+            rx.iter().collect::<Vec<String>>()
+        );
     }
 }
@@ -13,8 +13,7 @@
 //!         let ws_client = BinanceSpotWSClient::new(tx, None).await;
 //!         ws_client.subscribe_trade(&symbols).await;
 //!         // run for 5 seconds
-//!         let _ = tokio::time::timeout(std::time::Duration::from_secs(5), ws_client.run()).await;
-//!         ws_client.close();
+//!         ws_client.run(Some(std::time::Duration::from_secs(5))).await.unwrap();
 //!     });
 //!
 //!     let mut messages = Vec::new();
@@ -34,6 +33,13 @@
 //! * `subscribe_ticker(&self, symbols: &[String])`
 //! * `subscribe_candlestick(&self, symbol_interval_list: &[(String, usize)])`
 //!
+//! A few exchanges push data of all symbols in one channel, which are covered
+//! by `subscribe_all_trade(&self)`, `subscribe_all_bbo(&self)` and
+//! `subscribe_all_ticker(&self)`. Derivative markets of some exchanges also
+//! provide `subscribe_index_price(&self, symbols: &[String])`,
+//! `subscribe_mark_price(&self, symbols: &[String])` and
+//! `subscribe_funding_rate(&self, symbols: &[String])`.
+//!
 //! They are easier to use and cover most user scenarios.
 //!
 //! ## Low Level APIs
@@ -45,6 +51,22 @@
 //! * `unsubscribe(&self, topics: &[(String, String)])`
 //! * `send(&self, commands: &[String])`
 //!
+//! ## Reusing Connections
+//!
+//! Each client owns exactly one websocket connection, which is established in
+//! `new()`. Instead of creating a short-lived client per query, wrap a client
+//! in an `Arc`, call `run(None)` in a spawned task, then `subscribe()` and
+//! `unsubscribe()` topics on demand. Messages of combined streams, e.g.,
+//! Binance, carry the stream name, which can be used to route them.
+//!
+//! The consumer of `rx` can also stop a subscription upon a message, by
+//! calling `unsubscribe()` or `close()` on a clone of the `Arc`. It's safe
+//! because messages are handed over by a channel, the consumer never runs on
+//! the task that reads the websocket. Since these methods are async, a
+//! consumer on a plain thread calls them via `Handle::block_on()`, which must
+//! not be the thread driving `run()` on a `current_thread` runtime. Messages
+//! already in flight are still received after unsubscribing.
+//!
 //! ## OrderBook Data Categories
 //!
 //! Each orderbook has three properties: `aggregation`, `frequency` and `depth`.
@@ -58,14 +80,46 @@
 //!   original orderbook.
 //! * Level2 data is aggregated by price level, updated per tick.
 //! * Level3 data is the original orderbook, which is not aggregated.
+//!
+//! ## Runtimes
+//!
+//! Clients don't require the `multi_thread` flavor of tokio, they run on a
+//! `current_thread` runtime too. Since `tx` is a `std::sync::mpsc::Sender`,
+//! don't block the runtime thread on the receiving end while a client is
+//! running, consume messages from another thread or after `run()` returns.
+//!
+//! ## TLS
+//!
+//! Websocket and HTTP connections use rustls by default, which doesn't depend
+//! on OpenSSL. To use the platform TLS library instead, disable default
+//! features and enable the `native-tls` feature. One of the two features must
+//! be enabled.
+//!
+//! ## Logging
+//!
+//! This package logs connection state changes, unparseable frames and dropped
+//! messages via the [log](https://crates.io/crates/log) crate, plug in any
+//! logger implementation and set `RUST_LOG=crypto_ws_client=debug` to get a
+//! full trace.
+
+#[cfg(all(feature = "rustls-tls", feature = "native-tls"))]
+compile_error!("Features `rustls-tls` and `native-tls` are mutually exclusive");
+
+#[cfg(not(any(feature = "rustls-tls", feature = "native-tls")))]
+compile_error!("Either feature `rustls-tls` or `native-tls` must be enabled");
 
 mod clients;
 mod common;
 
-pub use common::ws_client::WSClient;
+pub use common::{
+    candlestick::interval_seconds_to_exchange_string,
+    message_queue::{BackPressure, MessageReceiver},
+    ws_client::{BboCadence, Channel, ConnectionInfo, WSClient, WSClientMetrics, WSError},
+    ws_client_builder::{FromWSClientBuilder, WSClientBuilder},
+};
 
 pub use clients::{
     binance::*, binance_option::*, bitfinex::*, bitget::*, bithumb::*, bitmex::*, bitstamp::*,
-    bitz::*, bybit::*, coinbase_pro::*, deribit::*, dydx::*, ftx::*, gate::*, huobi::*, kraken::*,
-    kucoin::*, mexc::*, okx::*, zb::*, zbg::*,
+    bitz::*, bybit::*, coinbase::*, coinbase_pro::*, deribit::*, dydx::*, ftx::*, gate::*,
+    huobi::*, kraken::*, kucoin::*, mexc::*, okx::*, zb::*, zbg::*,
 };
@@ -3,7 +3,7 @@ mod utils;
 
 #[cfg(test)]
 mod binance_spot {
-    use crypto_ws_client::{BinanceSpotWSClient, WSClient};
+    use crypto_ws_client::{BinanceSpotWSClient, Channel, WSClient};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe() {
@@ -46,6 +46,18 @@ mod binance_spot {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_typed() {
+        gen_test_code!(
+            BinanceSpotWSClient,
+            subscribe_typed,
+            &[
+                (Channel::AggTrade, "BTCUSDT".to_string()),
+                (Channel::Kline(60), "BTCUSDT".to_string())
+            ]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_ticker() {
         gen_test_code!(
@@ -64,6 +76,11 @@ mod binance_spot {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_all_ticker() {
+        gen_test_code!(BinanceSpotWSClient, subscribe_all_ticker);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_bbo() {
         gen_test_code!(
@@ -91,6 +108,32 @@ mod binance_spot {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_l2_with_depth() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tokio::task::spawn(async move {
+            let ws_client = BinanceSpotWSClient::new(tx, None).await;
+            ws_client.subscribe_l2(&["BTCUSDT".to_string()], Some(20), Some(100)).await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
+        });
+
+        let msg = rx.recv().unwrap();
+        assert!(msg.contains("btcusdt@depth20@100ms"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_orderbook_topk_with_depth() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tokio::task::spawn(async move {
+            let ws_client = BinanceSpotWSClient::new(tx, None).await;
+            ws_client.subscribe_orderbook_topk_with_depth(&["BTCUSDT".to_string()], 10).await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
+        });
+
+        let msg = rx.recv().unwrap();
+        assert!(msg.contains("btcusdt@depth10"));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_candlestick() {
         gen_test_subscribe_candlestick!(
@@ -102,6 +145,15 @@ mod binance_spot {
             &[("BTCUSDT".to_string(), 2592000), ("ETHUSDT".to_string(), 2592000)]
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_candlestick_raw() {
+        gen_test_code!(
+            BinanceSpotWSClient,
+            subscribe_candlestick_raw,
+            &[("BTCUSDT".to_string(), "1M".to_string()), ("ETHUSDT".to_string(), "1w".to_string())]
+        );
+    }
 }
 
 #[cfg(test)]
@@ -339,6 +391,11 @@ mod binance_inverse_swap {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_all_bbo_typed() {
+        gen_test_code!(BinanceInverseWSClient, subscribe_all_bbo);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_trade() {
         gen_test_code!(
@@ -375,6 +432,24 @@ mod binance_inverse_swap {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_index_price() {
+        gen_test_code!(
+            BinanceInverseWSClient,
+            subscribe_index_price,
+            &["btcusd".to_string(), "ethusd".to_string()]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_mark_price() {
+        gen_test_code!(
+            BinanceInverseWSClient,
+            subscribe_mark_price,
+            &["btcusd_perp".to_string(), "ethusd_perp".to_string()]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_orderbook() {
         gen_test_code!(
@@ -414,6 +489,15 @@ mod binance_inverse_swap {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_funding_rate_api() {
+        gen_test_code!(
+            BinanceInverseWSClient,
+            subscribe_funding_rate,
+            &["btcusd_perp".to_string()]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_funding_rate_all() {
         gen_test_code!(
@@ -473,6 +557,11 @@ mod binance_linear_swap {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_all_ticker() {
+        gen_test_code!(BinanceLinearWSClient, subscribe_all_ticker);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_bbo() {
         gen_test_code!(
@@ -482,6 +571,15 @@ mod binance_linear_swap {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_mark_price() {
+        gen_test_code!(
+            BinanceLinearWSClient,
+            subscribe_mark_price,
+            &["BTCUSDT".to_string(), "ETHUSDT".to_string()]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_orderbook() {
         gen_test_code!(
@@ -521,6 +619,11 @@ mod binance_linear_swap {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_funding_rate_api() {
+        gen_test_code!(BinanceLinearWSClient, subscribe_funding_rate, &["BTCUSDT".to_string()]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_funding_rate_all() {
         gen_test_code!(
@@ -12,6 +12,16 @@ async fn bitmex_instrument() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn subscribe_all_trade() {
+    gen_test_code!(BitmexWSClient, subscribe_all_trade);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn subscribe_all_bbo() {
+    gen_test_code!(BitmexWSClient, subscribe_all_bbo);
+}
+
 #[cfg(test)]
 mod bitmex_inverse_swap {
     use crypto_ws_client::{BitmexWSClient, WSClient};
@@ -1,6 +1,63 @@
 #[macro_use]
 mod utils;
 
+#[cfg(test)]
+mod bybit_spot {
+    use crypto_ws_client::{BybitSpotWSClient, WSClient};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe() {
+        gen_test_code!(
+            BybitSpotWSClient,
+            subscribe,
+            &[
+                ("publicTrade".to_string(), "BTCUSDT".to_string()),
+                ("publicTrade".to_string(), "ETHUSDT".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_raw_json() {
+        gen_test_code!(
+            BybitSpotWSClient,
+            send,
+            &[r#"{"op":"subscribe","args":["publicTrade.BTCUSDT","publicTrade.ETHUSDT"]}"#
+                .to_string()]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_trade() {
+        gen_test_code!(
+            BybitSpotWSClient,
+            subscribe_trade,
+            &["BTCUSDT".to_string(), "ETHUSDT".to_string()]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_orderbook() {
+        gen_test_code!(BybitSpotWSClient, subscribe_orderbook, &["BTCUSDT".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_ticker() {
+        gen_test_code!(BybitSpotWSClient, subscribe_ticker, &["BTCUSDT".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_bbo() {
+        gen_test_code!(BybitSpotWSClient, subscribe_bbo, &["BTCUSDT".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_candlestick() {
+        gen_test_subscribe_candlestick!(BybitSpotWSClient, &[("BTCUSDT".to_string(), 60)]);
+        gen_test_subscribe_candlestick!(BybitSpotWSClient, &[("BTCUSDT".to_string(), 2592000)]);
+    }
+}
+
 #[cfg(test)]
 mod bybit_inverse_future {
     use crypto_ws_client::{BybitInverseWSClient, WSClient};
@@ -120,3 +177,60 @@ mod bybit_linear_swap {
         );
     }
 }
+
+#[cfg(test)]
+mod bybit_swap {
+    use crypto_ws_client::{BybitSwapWSClient, WSClient};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe() {
+        gen_test_code!(
+            BybitSwapWSClient,
+            subscribe,
+            &[
+                ("publicTrade".to_string(), "BTCUSDT".to_string()),
+                ("publicTrade".to_string(), "ETHUSDT".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_raw_json() {
+        gen_test_code!(
+            BybitSwapWSClient,
+            send,
+            &[r#"{"op":"subscribe","args":["publicTrade.BTCUSDT","publicTrade.ETHUSDT"]}"#
+                .to_string()]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_trade() {
+        gen_test_code!(
+            BybitSwapWSClient,
+            subscribe_trade,
+            &["BTCUSDT".to_string(), "ETHUSDT".to_string()]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_orderbook() {
+        gen_test_code!(BybitSwapWSClient, subscribe_orderbook, &["BTCUSDT".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_ticker() {
+        gen_test_code!(BybitSwapWSClient, subscribe_ticker, &["BTCUSDT".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_bbo() {
+        gen_test_code!(BybitSwapWSClient, subscribe_bbo, &["BTCUSDT".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_candlestick() {
+        gen_test_subscribe_candlestick!(BybitSwapWSClient, &[("BTCUSDT".to_string(), 60)]);
+        gen_test_subscribe_candlestick!(BybitSwapWSClient, &[("BTCUSDT".to_string(), 2592000)]);
+    }
+}
@@ -45,8 +45,7 @@ mod huobi_spot {
             let ws_client = HuobiSpotWSClient::new(tx, Some("wss://api.huobi.pro/feed")).await;
             ws_client.subscribe_orderbook(&["btcusdt".to_string()]).await;
             // run for 60 seconds at most
-            let _ = tokio::time::timeout(std::time::Duration::from_secs(60), ws_client.run()).await;
-            ws_client.close().await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
         });
 
         rx.into_iter().next().expect("should has at least 1 element");
@@ -173,8 +172,7 @@ mod huobi_linear_swap {
                 .send(&[r#"{"topic":"public.BTC-USDT.funding_rate","op":"sub"}"#.to_string()])
                 .await;
             // run for 60 seconds at most
-            let _ = tokio::time::timeout(std::time::Duration::from_secs(60), ws_client.run()).await;
-            ws_client.close().await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
         });
 
         rx.into_iter().next().expect("should has at least 1 element");
@@ -191,8 +189,7 @@ mod huobi_linear_swap {
             .await;
             ws_client.send(&[r#"{"topic":"public.*.funding_rate","op":"sub"}"#.to_string()]).await;
             // run for 60 seconds at most
-            let _ = tokio::time::timeout(std::time::Duration::from_secs(60), ws_client.run()).await;
-            ws_client.close().await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
         });
 
         rx.into_iter().next().expect("should has at least 1 element");
@@ -261,8 +258,7 @@ mod huobi_inverse_swap {
                 .send(&[r#"{"topic":"public.BTC-USD.funding_rate","op":"sub"}"#.to_string()])
                 .await;
             // run for 60 seconds at most
-            let _ = tokio::time::timeout(std::time::Duration::from_secs(60), ws_client.run()).await;
-            ws_client.close().await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
         });
 
         rx.into_iter().next().expect("should has at least 1 element");
@@ -277,8 +273,7 @@ mod huobi_inverse_swap {
                     .await;
             ws_client.send(&[r#"{"topic":"public.*.funding_rate","op":"sub"}"#.to_string()]).await;
             // run for 60 seconds at most
-            let _ = tokio::time::timeout(std::time::Duration::from_secs(60), ws_client.run()).await;
-            ws_client.close().await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
         });
 
         rx.into_iter().next().expect("should has at least 1 element");
@@ -3,7 +3,7 @@ mod utils;
 
 #[cfg(test)]
 mod mexc_spot {
-    use crypto_ws_client::{MexcSpotWSClient, WSClient};
+    use crypto_ws_client::{Channel, MexcSpotWSClient, WSClient};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe() {
@@ -18,6 +18,31 @@ mod mexc_spot {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_typed() {
+        gen_test_code!(
+            MexcSpotWSClient,
+            subscribe_typed,
+            &[
+                (Channel::Trade, "BTC_USDT".to_string()),
+                (Channel::Kline(60), "BTC_USDT".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn run_for_duration() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ws_client = MexcSpotWSClient::new(tx, None).await;
+        ws_client.subscribe(&[("deal".to_string(), "BTC_USDT".to_string())]).await;
+        // returns after 5 seconds
+        ws_client.run(Some(std::time::Duration::from_secs(5))).await.unwrap();
+        drop(ws_client);
+
+        let messages = rx.iter().collect::<Vec<String>>();
+        assert!(!messages.is_empty());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_raw_json() {
         gen_test_code!(
@@ -54,6 +79,18 @@ mod mexc_spot {
         gen_test_code!(MexcSpotWSClient, subscribe_orderbook_topk, &["BTC_USDT".to_string()]);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_orderbook_topk_with_depth() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tokio::task::spawn(async move {
+            let ws_client = MexcSpotWSClient::new(tx, None).await;
+            ws_client.subscribe_orderbook_topk_with_depth(&["BTC_USDT".to_string()], 10).await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
+        });
+
+        assert!(rx.recv().is_ok());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_candlestick() {
         gen_test_subscribe_candlestick!(
@@ -78,6 +115,11 @@ mod mexc_spot {
     async fn subscribe_overview() {
         gen_test_code!(MexcSpotWSClient, send, &[r#"{"op":"sub.overview"}"#.to_string()]);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_all_ticker() {
+        gen_test_code!(MexcSpotWSClient, subscribe_all_ticker);
+    }
 }
 
 #[cfg(test)]
@@ -115,6 +157,11 @@ mod mexc_linear_swap {
         gen_test_code!(MexcSwapWSClient, subscribe_ticker, &["BTC_USDT".to_string()]);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_funding_rate() {
+        gen_test_code!(MexcSwapWSClient, subscribe_funding_rate, &["BTC_USDT".to_string()]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_orderbook() {
         gen_test_code!(MexcSwapWSClient, subscribe_orderbook, &["BTC_USDT".to_string()]);
@@ -125,6 +172,18 @@ mod mexc_linear_swap {
         gen_test_code!(MexcSwapWSClient, subscribe_orderbook_topk, &["BTC_USDT".to_string()]);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_orderbook_topk_with_depth() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tokio::task::spawn(async move {
+            let ws_client = MexcSwapWSClient::new(tx, None).await;
+            ws_client.subscribe_orderbook_topk_with_depth(&["BTC_USDT".to_string()], 10).await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
+        });
+
+        assert!(rx.recv().is_ok());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_candlestick() {
         gen_test_subscribe_candlestick!(MexcSwapWSClient, &[("BTC_USDT".to_string(), 60)]);
@@ -146,6 +205,11 @@ mod mexc_inverse_swap {
         gen_test_code!(MexcSwapWSClient, subscribe_ticker, &["BTC_USD".to_string()]);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_funding_rate() {
+        gen_test_code!(MexcSwapWSClient, subscribe_funding_rate, &["BTC_USD".to_string()]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn subscribe_orderbook() {
         gen_test_code!(MexcSwapWSClient, subscribe_orderbook, &["BTC_USD".to_string()]);
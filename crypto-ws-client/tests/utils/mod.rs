@@ -1,12 +1,27 @@
 macro_rules! gen_test_code {
+    ($client:ident, $func_name:ident) => {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tokio::task::spawn(async move {
+            let ws_client = $client::new(tx, None).await;
+            ws_client.$func_name().await;
+            // run for 60 seconds at most
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
+        });
+
+        let mut messages = Vec::<String>::new();
+        for msg in rx {
+            messages.push(msg);
+            break;
+        }
+        assert!(!messages.is_empty());
+    };
     ($client:ident, $func_name:ident, $symbols:expr) => {
         let (tx, rx) = std::sync::mpsc::channel();
         tokio::task::spawn(async move {
             let ws_client = $client::new(tx, None).await;
             ws_client.$func_name($symbols).await;
             // run for 60 seconds at most
-            let _ = tokio::time::timeout(std::time::Duration::from_secs(60), ws_client.run()).await;
-            ws_client.close().await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
         });
 
         let mut messages = Vec::<String>::new();
@@ -26,8 +41,7 @@ macro_rules! gen_test_subscribe_candlestick {
             let ws_client = $client::new(tx, None).await;
             ws_client.subscribe_candlestick($symbol_interval_list).await;
             // run for 60 seconds at most
-            let _ = tokio::time::timeout(std::time::Duration::from_secs(60), ws_client.run()).await;
-            ws_client.close().await;
+            ws_client.run(Some(std::time::Duration::from_secs(60))).await.unwrap();
         });
 
         let mut messages = Vec::<String>::new();